@@ -1,2 +1,5 @@
 pub mod sql;
 pub mod sync;
+
+/// 生成的 FromRow 实现通过 easy_sqlx_core::sqlx 引用 sqlx
+pub use sqlx;
@@ -1,8 +1,9 @@
 use std::future::Future;
 
+use futures::Stream;
 use sqlx::{Database, Error, Executor, FromRow};
 
-use crate::sql::dialects::page::{PageRequest, PageResult};
+use crate::sql::dialects::page::{CursorRequest, CursorResult, PageRequest, PageResult};
 
 pub trait ExecuteBuilder {
     type DB: Database;
@@ -13,6 +14,29 @@ pub trait ExecuteBuilder {
     ) -> impl Future<Output = Result<<Self::DB as Database>::QueryResult, Error>>
     where
         for<'e> &'e mut C: Executor<'e, Database = Self::DB>;
+
+    /// 执行并获取 returning 子句返回的记录，未设置 returning 时返回所有列
+    fn execute_returning<C, O>(&self, conn: &mut C) -> impl Future<Output = Result<Vec<O>, Error>>
+    where
+        for<'e> &'e mut C: Executor<'e, Database = Self::DB>,
+        for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
+        O: Send + Unpin;
+
+    /// 执行并获取 returning 子句返回的第一条记录，没有记录时返回 RowNotFound
+    fn execute_returning_one<C, O>(&self, conn: &mut C) -> impl Future<Output = Result<O, Error>>
+    where
+        for<'e> &'e mut C: Executor<'e, Database = Self::DB>,
+        for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
+        O: Send + Unpin,
+    {
+        async move {
+            self.execute_returning(conn)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or(Error::RowNotFound)
+        }
+    }
 }
 
 pub trait QueryBuilder<'a> {
@@ -48,6 +72,15 @@ pub trait QueryBuilder<'a> {
         O: std::marker::Send,
         O: Unpin;
 
+    /// 以流的方式逐条获取记录，不会将全部记录读入内存，适用于导出大量数据
+    fn stream<'e, 'c: 'e, E, O>(self, executor: E) -> impl Stream<Item = Result<O, Error>> + 'e
+    where
+        E: 'e + Executor<'c, Database = Self::DB>,
+        for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
+        O: 'e,
+        O: std::marker::Send,
+        O: Unpin;
+
     /// 分页查询
     fn page<'e, 'c: 'e, E, O>(
         &self,
@@ -62,6 +95,21 @@ pub trait QueryBuilder<'a> {
         O: std::marker::Send,
         O: Unpin;
 
+    /// 游标（keyset）分页查询
+    /// 按照 order_by 设置的排序列定位（未设置排序时按主键升序），排序列的值不能为 null
+    /// 首页传入的游标为 None，之后传入上一页返回的 next_cursor
+    fn cursor<'e, 'c: 'e, E, O>(
+        &self,
+        executor: E,
+        req: &CursorRequest,
+    ) -> impl Future<Output = Result<CursorResult<O>, Error>>
+    where
+        E: 'e + Executor<'c, Database = Self::DB>,
+        for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
+        O: 'e,
+        O: std::marker::Send,
+        O: Unpin;
+
     /// 查询记录数
     fn count<'c, E>(&self, executor: E) -> impl Future<Output = Result<usize, Error>>
     where
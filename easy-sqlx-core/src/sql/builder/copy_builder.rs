@@ -0,0 +1,317 @@
+use std::borrow::Cow;
+
+use std::fmt::Write as _;
+
+use sqlx::PgConnection;
+
+use crate::sql::{
+    dialects::schema::{self, schema::Schema as _},
+    schema::{
+        column::Column,
+        table::TableSchema,
+        types::{sql_types, types::SqlType},
+    },
+    utils::value::Value,
+};
+
+/// 每次发送到数据库的数据大小
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+type Rows<'a> = Box<dyn Iterator<Item = Vec<Value>> + Send + 'a>;
+
+/// 使用 copy ... from stdin 批量导入数据
+/// 列默认为 TableSchema.columns 中的所有列，可以通过 columns 指定，每行的值必须与列一一对应
+/// 行数据在发送时逐行生成并编码，不会一次性复制全部数据
+pub struct CopyInBuilder<'a> {
+    table: Cow<'a, TableSchema>,
+    default_schema: &'a str,
+    columns: Vec<String>,
+    rows: Vec<Rows<'a>>,
+}
+
+impl std::fmt::Debug for CopyInBuilder<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CopyInBuilder")
+            .field("table", &self.table.name)
+            .field("default_schema", &self.default_schema)
+            .field("columns", &self.columns)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> CopyInBuilder<'a> {
+    pub fn new<T: Into<Cow<'a, TableSchema>>>(table: T) -> Self {
+        Self {
+            table: table.into(),
+            default_schema: "",
+            columns: vec![],
+            rows: vec![],
+        }
+    }
+
+    pub fn with_default_schema(mut self, schema: &'a str) -> Self {
+        self.default_schema = schema;
+        self
+    }
+
+    /// 导入的列，可以是数据库列名称或字段名称，未设置时为全部列
+    /// 未导入的列（如自增列）由数据库生成
+    pub fn columns<S: Into<String>>(mut self, cols: Vec<S>) -> Self {
+        self.columns = cols.into_iter().map(|c| c.into()).collect();
+        self
+    }
+
+    /// 添加多行数据
+    pub fn rows(self, rows: Vec<Vec<Value>>) -> Self {
+        self.rows_iter(rows)
+    }
+
+    /// 添加一行数据
+    pub fn row(self, row: Vec<Value>) -> Self {
+        self.rows_iter(std::iter::once(row))
+    }
+
+    /// 添加按需生成的多行数据，执行时逐行生成，适用于大量数据
+    pub fn rows_iter<I>(mut self, rows: I) -> Self
+    where
+        I: IntoIterator<Item = Vec<Value>>,
+        I::IntoIter: Send + 'a,
+    {
+        self.rows.push(Box::new(rows.into_iter()));
+        self
+    }
+
+    /// 导入的列，顺序与每行的值一致
+    fn copy_columns(&self) -> Result<Vec<&Column>, sqlx::Error> {
+        if self.columns.is_empty() {
+            return Ok(self.table.columns.iter().collect());
+        }
+        self.columns
+            .iter()
+            .map(|name| {
+                self.table
+                    .columns
+                    .iter()
+                    .find(|c| c.get_column_name() == *name || c.name == *name)
+                    .ok_or_else(|| sqlx::Error::ColumnNotFound(name.clone()))
+            })
+            .collect()
+    }
+
+    /// copy ... from stdin 语句
+    fn sql_copy_in(&self, columns: &[&Column]) -> String {
+        let schema = schema::new(self.default_schema.to_string());
+        let cols: Vec<String> = columns.iter().map(|c| c.get_column_name()).collect();
+        schema.sql_copy_in(&self.table, &cols)
+    }
+
+    /// 生成 copy 语句及按 copy text 格式编码的全部数据，不执行语句
+    /// 所有行一次性编码到内存中，用于测试或记录日志
+    pub fn to_sql(mut self) -> Result<(String, String), sqlx::Error> {
+        let rows = std::mem::take(&mut self.rows);
+        let columns = self.copy_columns()?;
+        let mut buf = String::new();
+        for row in rows.into_iter().flatten() {
+            encode_row(&self.table.name, &columns, &row, &mut buf)?;
+        }
+        Ok((self.sql_copy_in(&columns), buf))
+    }
+
+    /// 执行导入，返回导入的行数
+    /// 导入失败时终止 copy，已发送的数据不会写入
+    pub async fn execute(mut self, conn: &mut PgConnection) -> Result<u64, sqlx::Error> {
+        let rows = std::mem::take(&mut self.rows);
+        let columns = self.copy_columns()?;
+        let sql = self.sql_copy_in(&columns);
+
+        tracing::debug!("easy-sqlx: {}", sql);
+
+        let mut copy = conn.copy_in_raw(&sql).await?;
+        let mut buf = String::new();
+        for row in rows.into_iter().flatten() {
+            if let Err(err) = encode_row(&self.table.name, &columns, &row, &mut buf) {
+                copy.abort(err.to_string()).await?;
+                return Err(err);
+            }
+            if buf.len() >= COPY_BUFFER_SIZE {
+                copy.send(buf.as_bytes()).await?;
+                buf.clear();
+            }
+        }
+        if !buf.is_empty() {
+            copy.send(buf.as_bytes()).await?;
+        }
+        copy.finish().await
+    }
+}
+
+/// 按 copy text 格式编码一行数据，列之间使用 \t 分隔，以 \n 结尾
+fn encode_row(
+    table: &str,
+    columns: &[&Column],
+    row: &[Value],
+    buf: &mut String,
+) -> Result<(), sqlx::Error> {
+    if row.len() != columns.len() {
+        return Err(sqlx::Error::Configuration(
+            format!(
+                "copy row of table {table} has {} values, expected {}",
+                row.len(),
+                columns.len()
+            )
+            .into(),
+        ));
+    }
+    for (n, (col, value)) in columns.iter().zip(row.iter()).enumerate() {
+        if n > 0 {
+            buf.push('\t');
+        }
+        encode_value(&col.typ, value, buf).map_err(|err| {
+            sqlx::Error::Encode(format!("column {}: {err}", col.get_column_name()).into())
+        })?;
+    }
+    buf.push('\n');
+    Ok(())
+}
+
+/// 按列的数据类型将值编码为 copy text 格式，null 编码为 \N
+fn encode_value(typ: &SqlType, value: &Value, buf: &mut String) -> Result<(), String> {
+    match value {
+        Value::Bool(Some(v)) => buf.push(if *v { 't' } else { 'f' }),
+        Value::Short(Some(v)) => write_display(buf, v),
+        Value::Int(Some(v)) => write_display(buf, v),
+        Value::Long(Some(v)) => write_display(buf, v),
+        Value::Float(Some(v)) => write_float(buf, *v as f64),
+        Value::Double(Some(v)) => write_float(buf, *v),
+        Value::Text(Some(v)) => escape_text(buf, v),
+        Value::Binary(Some(v)) => {
+            // bytea 使用 hex 格式 \x...，反斜杠需要转义
+            buf.push_str("\\\\x");
+            for b in v {
+                write_display(buf, format_args!("{b:02x}"));
+            }
+        }
+        Value::ChronoDate(Some(v)) => match typ.name.as_str() {
+            sql_types::DATE => write_display(buf, v.format("%Y-%m-%d")),
+            sql_types::TIME | sql_types::TIME_WITHOUT_TIME_ZONE => {
+                write_display(buf, v.format("%H:%M:%S%.f"))
+            }
+            _ => write_display(buf, v.format("%Y-%m-%d %H:%M:%S%.f")),
+        },
+        Value::Array(_) => return Err(format!("array value is not supported by copy ({})", typ.name)),
+        _ => buf.push_str("\\N"),
+    }
+    Ok(())
+}
+
+fn write_display(buf: &mut String, v: impl std::fmt::Display) {
+    // 写入 String 不会失败
+    let _ = write!(buf, "{v}");
+}
+
+fn write_float(buf: &mut String, v: f64) {
+    if v.is_nan() {
+        buf.push_str("NaN");
+    } else if v.is_infinite() {
+        buf.push_str(if v > 0.0 { "Infinity" } else { "-Infinity" });
+    } else {
+        write_display(buf, v);
+    }
+}
+
+/// 转义文本中的 \ 及控制字符
+fn escape_text(buf: &mut String, v: &str) {
+    for c in v.chars() {
+        match c {
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            _ => buf.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::builder::test_schema::column;
+
+    fn typed(name: &str, typ: &str) -> Column {
+        Column {
+            typ: SqlType {
+                name: typ.to_string(),
+                ..Default::default()
+            },
+            ..column(name)
+        }
+    }
+
+    fn encode(columns: &[Column], row: Vec<Value>) -> Result<String, sqlx::Error> {
+        let columns: Vec<&Column> = columns.iter().collect();
+        let mut buf = String::new();
+        encode_row("user", &columns, &row, &mut buf)?;
+        Ok(buf)
+    }
+
+    #[test]
+    fn text_escapes_backslash_and_control_characters() {
+        let columns = [typed("name", sql_types::TEXT)];
+        let row = vec![Value::from("a\tb\nc\rd\\e".to_string())];
+        assert_eq!(encode(&columns, row).unwrap(), "a\\tb\\nc\\rd\\\\e\n");
+    }
+
+    #[test]
+    fn null_and_literal_backslash_n_are_distinguished() {
+        let columns = [
+            typed("name", sql_types::TEXT),
+            typed("memo", sql_types::TEXT),
+        ];
+        let row = vec![Value::Text(None), Value::from("\\N".to_string())];
+        assert_eq!(encode(&columns, row).unwrap(), "\\N\t\\\\N\n");
+    }
+
+    #[test]
+    fn bytea_is_hex_encoded() {
+        let columns = [typed("blob", sql_types::BYTEA)];
+        let row = vec![Value::Binary(Some(vec![0x00, 0xab, 0x10]))];
+        assert_eq!(encode(&columns, row).unwrap(), "\\\\x00ab10\n");
+    }
+
+    #[test]
+    fn special_floats_use_postgres_spelling() {
+        let columns = [
+            typed("a", sql_types::DOUBLE),
+            typed("b", sql_types::DOUBLE),
+            typed("c", sql_types::REAL),
+            typed("d", sql_types::DOUBLE),
+        ];
+        let row = vec![
+            Value::from(f64::NAN),
+            Value::from(f64::INFINITY),
+            Value::from(f32::NEG_INFINITY),
+            Value::from(1.5f64),
+        ];
+        assert_eq!(
+            encode(&columns, row).unwrap(),
+            "NaN\tInfinity\t-Infinity\t1.5\n"
+        );
+    }
+
+    #[test]
+    fn array_values_are_rejected() {
+        let columns = [typed("tags", sql_types::TEXT)];
+        let row = vec![Value::Array(vec![Value::from("a".to_string())])];
+        assert!(matches!(encode(&columns, row), Err(sqlx::Error::Encode(_))));
+    }
+
+    #[test]
+    fn row_length_must_match_columns() {
+        let columns = [typed("id", sql_types::BIG_INT), typed("name", sql_types::TEXT)];
+        let row = vec![Value::from(1i64)];
+        assert!(matches!(
+            encode(&columns, row),
+            Err(sqlx::Error::Configuration(_))
+        ));
+    }
+}
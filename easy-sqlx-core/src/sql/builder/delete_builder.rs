@@ -1,27 +1,54 @@
+use std::{borrow::Cow, marker::PhantomData};
+
 use crate::sql::{
     dialects::{
-        condition::{Condition, Where, WhereAppend},
+        condition::{Condition, Operator, Where, WhereAppend},
         schema::{self, schema::Schema},
     },
+    entity::Entity,
     schema::table::TableSchema,
+    typed::Typed,
+    utils::value::Value,
 };
 
-use super::builder::ExecuteBuilder;
-use sqlx::{Database, Execute as _};
+use super::{builder::ExecuteBuilder, error::BuilderError, statement::Statement};
+use sqlx::{Database, Execute as _, FromRow};
 
 #[derive(Debug)]
-pub struct DeleteBuilder<'a> {
-    table: TableSchema,
+pub struct DeleteBuilder<'a, T = ()> {
+    table: Cow<'a, TableSchema>,
     default_schema: &'a str,
     wh: Option<Where>,
+    returning: Vec<String>,
+    /// 允许没有 where 条件时操作全部记录
+    all_rows: bool,
+    force: bool,
+    entity: PhantomData<fn() -> T>,
 }
 
 impl<'a> DeleteBuilder<'a> {
-    pub fn new(table: TableSchema) -> Self {
+    pub fn new<T: Into<Cow<'a, TableSchema>>>(table: T) -> Self {
+        Self::create(table.into())
+    }
+}
+
+impl<'a, T: Entity> DeleteBuilder<'a, T> {
+    /// 实体 T 的删除，条件只接受属于 T 的带类型列，如 User::ID.eq(1)
+    pub fn for_entity() -> Self {
+        Self::create(T::table_schema().into())
+    }
+}
+
+impl<'a, T> DeleteBuilder<'a, T> {
+    fn create(table: Cow<'a, TableSchema>) -> Self {
         Self {
             table,
             default_schema: "",
             wh: None,
+            returning: vec![],
+            all_rows: false,
+            force: false,
+            entity: PhantomData,
         }
     }
 
@@ -29,6 +56,99 @@ impl<'a> DeleteBuilder<'a> {
         self.default_schema = schema;
         self
     }
+
+    /// 确认删除全部记录，未调用时没有 where 条件的语句返回 BuilderError::Unconditioned
+    pub fn all_rows(mut self) -> Self {
+        self.all_rows = true;
+        self
+    }
+
+    /// 检查是否有 where 条件，没有条件并且未调用 all_rows 时拒绝执行
+    fn check_condition(&self, sql: &str) -> Result<(), sqlx::Error> {
+        let has_condition = self.wh.as_ref().is_some_and(|w| !w.is_empty());
+        if !has_condition && !self.all_rows {
+            tracing::error!("easy-sqlx: refused to execute without where condition: {sql}");
+            return Err(BuilderError::Unconditioned(sql.to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// returning 子句返回的列，使用 execute_returning 执行时生效
+    pub fn returning<S: Into<String>>(mut self, cols: Vec<S>) -> Self {
+        self.returning = cols.into_iter().map(|c| c.into()).collect();
+        self
+    }
+
+    /// 物理删除记录，忽略表的 soft_delete 设置
+    pub fn force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+
+    /// 软删除列，表未设置 soft_delete 或调用了 force 时返回 None
+    fn soft_delete_column(&self) -> Option<&String> {
+        if self.force {
+            None
+        } else {
+            self.table.soft_delete.as_ref()
+        }
+    }
+
+    /// 软删除时设置的删除时间
+    fn deleted_at(&self) -> Option<Value> {
+        self.soft_delete_column().map(|_| Value::now())
+    }
+
+    /// returning 的列，未设置时返回所有列
+    fn returning_columns(&self) -> Vec<String> {
+        if self.returning.is_empty() {
+            self.table
+                .columns
+                .iter()
+                .map(|c| c.get_query_column_name())
+                .collect()
+        } else {
+            self.returning.clone()
+        }
+    }
+
+    /// 生成 execute 执行的 sql 及按顺序绑定的参数，不执行语句
+    /// returning 子句只在 execute_returning 执行时生成
+    pub fn to_sql(&self) -> (String, Vec<Value>) {
+        let mut params: Vec<Value> = self.deleted_at().into_iter().collect();
+        if let Some(w) = &self.wh {
+            params.extend(w.params());
+        }
+        (self.generate_sql(false), params)
+    }
+
+    /// 生成可重复执行的语句，参数可以重新设置
+    /// 软删除的删除时间在生成时确定
+    pub fn prepare(&self) -> Result<Statement, sqlx::Error> {
+        let (sql, params) = self.to_sql();
+        self.check_condition(&sql)?;
+        Ok(Statement::new(sql, params))
+    }
+
+    fn generate_sql(&self, returning: bool) -> String {
+        let schema = schema::new(self.default_schema.to_string());
+
+        let mut sql = if let Some(col) = self.soft_delete_column() {
+            // 软删除，只修改未删除的记录
+            let cond = Condition::column_expr(col.clone(), Operator::IsNull, "");
+            let wh = match self.wh.clone() {
+                Some(w) => w.and(cond),
+                None => Where::new(cond),
+            };
+            schema.sql_update_columns(&self.table, &vec![col.clone()], &[], Some(wh))
+        } else {
+            schema.sql_delete(&self.table, self.wh.clone())
+        };
+        if returning {
+            sql.push_str(&schema.sql_returning(&self.returning_columns()));
+        }
+        sql
+    }
 }
 impl<'a> WhereAppend<Condition> for DeleteBuilder<'a> {
     fn and(mut self, cond: Condition) -> Self {
@@ -70,10 +190,32 @@ impl<'a> WhereAppend<Where> for DeleteBuilder<'a> {
     }
 }
 
+impl<'a, T> WhereAppend<Typed<T, Condition>> for DeleteBuilder<'a, T> {
+    fn and(mut self, cond: Typed<T, Condition>) -> Self {
+        let cond = cond.into_inner();
+        if let Some(w) = self.wh {
+            self.wh = Some(w.and(cond));
+        } else {
+            self.wh = Some(Where::new(cond));
+        }
+        self
+    }
+
+    fn or(mut self, cond: Typed<T, Condition>) -> Self {
+        let cond = cond.into_inner();
+        if let Some(w) = self.wh {
+            self.wh = Some(w.or(cond));
+        } else {
+            self.wh = Some(Where::new(cond));
+        }
+        self
+    }
+}
+
 #[cfg(feature = "postgres")]
 use sqlx::Postgres;
 
-impl<'a> ExecuteBuilder for DeleteBuilder<'a> {
+impl<'a, T> ExecuteBuilder for DeleteBuilder<'a, T> {
     #[cfg(feature = "postgres")]
     type DB = Postgres;
 
@@ -84,13 +226,16 @@ impl<'a> ExecuteBuilder for DeleteBuilder<'a> {
     where
         for<'e> &'e mut C: sqlx::Executor<'e, Database = Self::DB>,
     {
-        let schema = schema::new(self.default_schema.to_string());
-
-        let sql = schema.sql_delete(&self.table, self.wh.clone());
+        let sql = self.generate_sql(false);
+        self.check_condition(&sql)?;
 
         let mut query: sqlx::query::Query<'_, Self::DB, <Self::DB as Database>::Arguments<'_>> =
             sqlx::query::<Self::DB>(&sql);
 
+        if let Some(v) = self.deleted_at() {
+            query = v.bind_to_query(query);
+        }
+
         if let Some(w) = &self.wh {
             query = w.bind_to_query(query);
         }
@@ -99,4 +244,93 @@ impl<'a> ExecuteBuilder for DeleteBuilder<'a> {
 
         query.execute(conn).await
     }
+
+    async fn execute_returning<C, O>(&self, conn: &mut C) -> Result<Vec<O>, sqlx::Error>
+    where
+        for<'e> &'e mut C: sqlx::Executor<'e, Database = Self::DB>,
+        for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
+        O: Send + Unpin,
+    {
+        let sql = self.generate_sql(true);
+        self.check_condition(&sql)?;
+        let mut query = sqlx::query_as::<Self::DB, O>(&sql);
+
+        if let Some(v) = self.deleted_at() {
+            query = v.bind_to_query_as(query);
+        }
+
+        if let Some(w) = &self.wh {
+            query = w.bind_to_query_as(query);
+        }
+
+        tracing::debug!("easy-sqlx: {}", query.sql());
+
+        query.fetch_all(conn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::{
+        builder::test_schema::{column, cond, pk, table},
+        schema::column::Column,
+    };
+
+    fn soft_deleted() -> TableSchema {
+        TableSchema {
+            soft_delete: Some("deleted_at".to_string()),
+            ..table(vec![
+                pk("id"),
+                Column {
+                    nullable: true,
+                    ..column("deleted_at")
+                },
+            ])
+        }
+    }
+
+    #[test]
+    fn soft_delete_updates_deleted_at() {
+        let builder = DeleteBuilder::new(soft_deleted()).and(cond("id", Operator::Eq, 1i64));
+        let (sql, params) = builder.to_sql();
+        assert_eq!(
+            sql,
+            r#"update "public"."user" set "deleted_at"=$1  where "id" = $2 and "deleted_at" is null"#
+        );
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[1], Value::from(1i64));
+    }
+
+    #[test]
+    fn force_deletes_rows() {
+        let builder = DeleteBuilder::new(soft_deleted())
+            .and(cond("id", Operator::Eq, 1i64))
+            .force();
+        let (sql, params) = builder.to_sql();
+        assert_eq!(sql, r#"delete from "public"."user"  where "id" = $1"#);
+        assert_eq!(params, vec![Value::from(1i64)]);
+    }
+
+    #[test]
+    fn soft_delete_without_condition_is_rejected() {
+        let err = DeleteBuilder::new(soft_deleted()).prepare().unwrap_err();
+        assert!(matches!(
+            BuilderError::from_sqlx(&err),
+            Some(BuilderError::Unconditioned(_))
+        ));
+    }
+
+    #[test]
+    fn delete_without_condition_requires_all_rows() {
+        let user = table(vec![pk("id")]);
+        let err = DeleteBuilder::new(user.clone()).prepare().unwrap_err();
+        assert!(matches!(
+            BuilderError::from_sqlx(&err),
+            Some(BuilderError::Unconditioned(_))
+        ));
+
+        let stmt = DeleteBuilder::new(user).all_rows().prepare().unwrap();
+        assert_eq!(stmt.sql(), r#"delete from "public"."user" "#);
+    }
 }
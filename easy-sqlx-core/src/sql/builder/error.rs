@@ -0,0 +1,52 @@
+use std::fmt::Display;
+
+/// 生成及执行语句时的错误，以 sqlx::Error::Configuration 返回
+/// 使用 BuilderError::from_sqlx 从 sqlx::Error 中获取
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuilderError {
+    /// 乐观锁版本不一致，记录已被其它事务修改或已不存在
+    StaleVersion(String),
+    /// 没有 where 条件的 update / delete，需要调用 all_rows() 确认操作全部记录
+    Unconditioned(String),
+}
+
+impl Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderError::StaleVersion(table) => {
+                write!(f, "stale version: no rows updated in table {table}")
+            }
+            BuilderError::Unconditioned(sql) => {
+                write!(
+                    f,
+                    "refused to execute without where condition, call all_rows() to allow: {sql}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
+impl From<BuilderError> for sqlx::Error {
+    fn from(err: BuilderError) -> Self {
+        sqlx::Error::Configuration(Box::new(err))
+    }
+}
+
+impl BuilderError {
+    /// 从 sqlx::Error 中获取 BuilderError，不是 BuilderError 时返回 None
+    /// ```rust,ignore
+    /// if let Err(err) = user.update().execute(&mut conn).await {
+    ///     if let Some(BuilderError::StaleVersion(_)) = BuilderError::from_sqlx(&err) {
+    ///         // 记录已被修改，重新加载后再更新
+    ///     }
+    /// }
+    /// ```
+    pub fn from_sqlx(err: &sqlx::Error) -> Option<&BuilderError> {
+        match err {
+            sqlx::Error::Configuration(err) => err.downcast_ref::<BuilderError>(),
+            _ => None,
+        }
+    }
+}
@@ -1,26 +1,58 @@
-use sqlx::{Database, Execute, Executor, Postgres};
+use std::{borrow::Cow, marker::PhantomData};
+
+use sqlx::{Database, Execute, Executor, FromRow, Postgres};
 
 use crate::sql::{
-    dialects::schema::{self, schema::Schema as _},
+    dialects::{
+        conflict::{ConflictAction, ConflictTarget, OnConflict},
+        schema::{self, schema::Schema},
+    },
+    entity::Entity,
     schema::table::TableSchema,
-    utils::pair::Pair,
+    typed::EntityArg,
+    utils::{pair::Pair, value::Value},
 };
 
-use super::builder::ExecuteBuilder;
+use super::{builder::ExecuteBuilder, statement::Statement};
 
 #[derive(Debug)]
-pub struct InsertBuilder<'a> {
-    table: TableSchema,
+pub struct InsertBuilder<'a, T = ()> {
+    table: Cow<'a, TableSchema>,
     default_schema: &'a str,
     columns: Vec<Pair>,
+    /// 多行数据，调用 rows / row 后为 Some
+    rows: Option<Vec<Vec<Pair>>>,
+    conflict: Option<OnConflict>,
+    returning: Vec<String>,
+    entity: PhantomData<fn() -> T>,
 }
 
+/// postgres 单条语句最多可绑定的参数个数
+const MAX_BIND_PARAMS: usize = 65535;
+
 impl<'a> InsertBuilder<'a> {
-    pub fn new(table: TableSchema) -> Self {
+    pub fn new<T: Into<Cow<'a, TableSchema>>>(table: T) -> Self {
+        Self::create(table.into())
+    }
+}
+
+impl<'a, T: Entity> InsertBuilder<'a, T> {
+    /// 实体 T 的插入，赋值只接受属于 T 的带类型列
+    pub fn for_entity() -> Self {
+        Self::create(T::table_schema().into())
+    }
+}
+
+impl<'a, T> InsertBuilder<'a, T> {
+    fn create(table: Cow<'a, TableSchema>) -> Self {
         Self {
             table,
             default_schema: "",
             columns: vec![],
+            rows: None,
+            conflict: None,
+            returning: vec![],
+            entity: PhantomData,
         }
     }
 
@@ -29,13 +61,228 @@ impl<'a> InsertBuilder<'a> {
         self
     }
 
-    pub fn set(mut self, pair: Pair)-> Self {
-        self.columns.push(pair);
+    pub fn set<P: EntityArg<T, Pair>>(mut self, pair: P) -> Self {
+        self.columns.push(pair.into_arg());
+        self
+    }
+
+    /// 添加多行数据，使用一条 insert 语句插入多行
+    /// 某行没有设置的列使用数据库默认值（default）
+    /// 参数个数超过 postgres 限制（65535）时自动拆分为多条语句执行，
+    /// 需要保证原子性时请在事务中执行
+    pub fn rows<P: EntityArg<T, Pair>>(mut self, rows: Vec<Vec<P>>) -> Self {
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(|p| p.into_arg()).collect::<Vec<Pair>>());
+        self.rows.get_or_insert_with(Vec::new).extend(rows);
+        self
+    }
+
+    /// 添加一行数据
+    pub fn row<P: EntityArg<T, Pair>>(mut self, row: Vec<P>) -> Self {
+        let row = row.into_iter().map(|p| p.into_arg()).collect();
+        self.rows.get_or_insert_with(Vec::new).push(row);
+        self
+    }
+
+    /// returning 子句返回的列，使用 execute_returning 执行时生效
+    pub fn returning<S: Into<String>>(mut self, cols: Vec<S>) -> Self {
+        self.returning = cols.into_iter().map(|c| c.into()).collect();
+        self
+    }
+
+    /// on conflict (列) 冲突目标为唯一约束的列
+    pub fn on_conflict<S: Into<String>>(mut self, cols: Vec<S>) -> Self {
+        let target = ConflictTarget::Columns(cols.into_iter().map(|c| c.into()).collect());
+        self.conflict_mut().target = target;
+        self
+    }
+
+    /// on conflict (列) 冲突目标为唯一索引，执行时根据索引名称获取索引的列
+    pub fn on_conflict_index<S: Into<String>>(mut self, name: S) -> Self {
+        self.conflict_mut().target = ConflictTarget::Index(name.into());
         self
     }
+
+    /// do nothing 发生冲突时忽略
+    pub fn do_nothing(mut self) -> Self {
+        self.conflict_mut().action = ConflictAction::Nothing;
+        self
+    }
+
+    /// do update set 列 = 值，可以多次调用
+    pub fn do_update<P: EntityArg<T, Pair>>(mut self, pair: P) -> Self {
+        let pair = pair.into_arg();
+        let conflict = self.conflict_mut();
+        if let ConflictAction::Update(pairs) = &mut conflict.action {
+            pairs.push(pair);
+        } else {
+            conflict.action = ConflictAction::Update(vec![pair]);
+        }
+        self
+    }
+
+    /// do update set 列 = excluded.列，使用插入的值更新
+    pub fn do_update_excluded<S: Into<String>>(mut self, cols: Vec<S>) -> Self {
+        let cols = cols.into_iter().map(|c| c.into()).collect();
+        self.conflict_mut().action = ConflictAction::UpdateExcluded(cols);
+        self
+    }
+
+    /// 未设置冲突目标时不指定冲突的列，只能与 do_nothing 一起使用，do update 执行时返回错误
+    fn conflict_mut(&mut self) -> &mut OnConflict {
+        self.conflict
+            .get_or_insert_with(|| OnConflict::new(ConflictTarget::Columns(vec![])))
+    }
+
+    /// 生成 on conflict 子句，param_index 为 do update set 第一个参数的序号
+    fn sql_conflict(&self, schema: &impl Schema, param_index: usize) -> Result<String, sqlx::Error> {
+        let Some(conflict) = &self.conflict else {
+            return Ok("".to_string());
+        };
+        let target = conflict.target_columns(&self.table).ok_or_else(|| {
+            sqlx::Error::Configuration(
+                format!(
+                    "conflict target index of table {} not found: {:?}",
+                    self.table.name, conflict.target
+                )
+                .into(),
+            )
+        })?;
+        if target.is_empty() && !matches!(conflict.action, ConflictAction::Nothing) {
+            return Err(sqlx::Error::Configuration(
+                format!(
+                    "on conflict do update of table {} requires a conflict target, use on_conflict or on_conflict_index",
+                    self.table.name
+                )
+                .into(),
+            ));
+        }
+        Ok(schema.sql_on_conflict(&target, &conflict.action, param_index))
+    }
+
+    /// do update set 需要绑定的参数
+    fn conflict_pairs(&self) -> &[Pair] {
+        self.conflict.as_ref().map(|c| c.pairs()).unwrap_or(&[])
+    }
+
+    /// 生成 execute 执行的 sql 及按顺序绑定的参数，不执行语句
+    /// 参数过多需要拆分为多条语句或多行插入没有数据时返回错误，此时使用 statements
+    pub fn to_sql(&self) -> Result<(String, Vec<Value>), sqlx::Error> {
+        let mut statements = self.statements()?;
+        if statements.len() != 1 {
+            return Err(sqlx::Error::Configuration(
+                format!(
+                    "insert into {} renders {} statements, use statements() instead",
+                    self.table.name,
+                    statements.len()
+                )
+                .into(),
+            ));
+        }
+        Ok(statements.remove(0))
+    }
+
+    /// 生成 execute 依次执行的所有语句及按顺序绑定的参数，不执行语句
+    /// 参数个数超过限制时拆分为多条语句，returning 子句只在 execute_returning 执行时生成
+    pub fn statements(&self) -> Result<Vec<(String, Vec<Value>)>, sqlx::Error> {
+        Ok(self
+            .render(false)?
+            .into_iter()
+            .map(|(sql, values)| {
+                let mut params = vec![];
+                for v in values {
+                    v.append_params(&mut params);
+                }
+                (sql, params)
+            })
+            .collect())
+    }
+
+    /// 生成可重复执行的语句，参数可以重新设置
+    /// 与 to_sql 相同，需要拆分为多条语句时返回错误
+    pub fn prepare(&self) -> Result<Statement, sqlx::Error> {
+        Ok(self.to_sql()?.into())
+    }
+
+    /// 生成插入语句及需要绑定的参数
+    /// 多行插入时参数个数超过限制拆分为多条语句
+    fn render(&self, returning: bool) -> Result<Vec<(String, Vec<&Value>)>, sqlx::Error> {
+        let mut rows: Vec<&Vec<Pair>> = vec![];
+        if !self.columns.is_empty() || self.rows.is_none() {
+            rows.push(&self.columns);
+        }
+        rows.extend(self.rows.iter().flatten());
+        if rows.is_empty() {
+            // 多行插入时没有数据
+            return Ok(vec![]);
+        }
+
+        // 所有行的列，按出现的顺序排列，某行没有的列使用 default
+        let mut cols: Vec<String> = vec![];
+        for row in &rows {
+            for pair in row.iter() {
+                if !cols.contains(&pair.name) {
+                    cols.push(pair.name.clone());
+                }
+            }
+        }
+        let cells: Vec<Vec<Option<&Value>>> = rows
+            .iter()
+            .map(|row| {
+                cols.iter()
+                    .map(|c| row.iter().find(|p| &p.name == c).map(|p| &p.value))
+                    .collect()
+            })
+            .collect();
+
+        let schema = schema::new(self.default_schema.to_string());
+        let returning_str = if returning {
+            schema.sql_returning(&self.returning_columns())
+        } else {
+            "".to_string()
+        };
+        let conflict_pairs = self.conflict_pairs();
+        // 没有列时使用 default values，每条语句只能插入一行
+        let chunk_size = if cols.is_empty() {
+            1
+        } else {
+            (MAX_BIND_PARAMS.saturating_sub(conflict_pairs.len()) / cols.len()).max(1)
+        };
+
+        let mut statements = vec![];
+        for chunk in cells.chunks(chunk_size) {
+            let presents: Vec<Vec<bool>> = chunk
+                .iter()
+                .map(|row| row.iter().map(|v| v.is_some()).collect())
+                .collect();
+            let mut values: Vec<&Value> = chunk.iter().flatten().flatten().copied().collect();
+
+            let mut sql = schema.sql_insert_rows(&self.table, &cols, &presents);
+            sql.push_str(&self.sql_conflict(&schema, values.len() + 1)?);
+            sql.push_str(&returning_str);
+
+            values.extend(conflict_pairs.iter().map(|pair| &pair.value));
+            statements.push((sql, values));
+        }
+        Ok(statements)
+    }
+
+    /// returning 的列，未设置时返回所有列
+    fn returning_columns(&self) -> Vec<String> {
+        if self.returning.is_empty() {
+            self.table
+                .columns
+                .iter()
+                .map(|c| c.get_query_column_name())
+                .collect()
+        } else {
+            self.returning.clone()
+        }
+    }
 }
 
-impl<'a> ExecuteBuilder for InsertBuilder<'a>
+impl<'a, T> ExecuteBuilder for InsertBuilder<'a, T>
 // <DB as sqlx::Database>::Arguments<'a>: IntoArguments<'a, DB>,
 {
     #[cfg(feature = "postgres")]
@@ -48,19 +295,134 @@ impl<'a> ExecuteBuilder for InsertBuilder<'a>
     where
         for<'e> &'e mut C: Executor<'e, Database = Self::DB>,
     {
-        let schema = schema::new(self.default_schema.to_string());
-        let cols: Vec<String> = self.columns.iter().map(|c| c.name.to_string()).collect();
-        let sql = schema.sql_insert_columns(&self.table, &cols);
+        let mut result = <Self::DB as Database>::QueryResult::default();
+        for (sql, values) in self.render(false)? {
+            let mut query: sqlx::query::Query<'_, Self::DB, <Self::DB as Database>::Arguments<'_>> =
+                sqlx::query::<Self::DB>(&sql);
 
-        let mut query: sqlx::query::Query<'_, Self::DB, <Self::DB as Database>::Arguments<'_>> =
-            sqlx::query::<Self::DB>(&sql);
+            for value in values {
+                query = value.bind_to_query(query);
+            }
 
-        for col in &self.columns {
-            query = col.value.bind_to_query(query);
+            tracing::debug!("easy-sqlx: {}", query.sql());
+
+            result.extend(Some(query.execute(&mut *conn).await?));
         }
+        Ok(result)
+    }
 
-        tracing::debug!("easy-sqlx: {}", query.sql());
+    async fn execute_returning<C, O>(&self, conn: &mut C) -> Result<Vec<O>, sqlx::Error>
+    where
+        for<'e> &'e mut C: Executor<'e, Database = Self::DB>,
+        for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
+        O: Send + Unpin,
+    {
+        let mut result = vec![];
+        for (sql, values) in self.render(true)? {
+            let mut query = sqlx::query_as::<Self::DB, O>(&sql);
+
+            for value in values {
+                query = value.bind_to_query_as(query);
+            }
+
+            tracing::debug!("easy-sqlx: {}", query.sql());
+
+            result.extend(query.fetch_all(&mut *conn).await?);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::builder::test_schema::{column, pair, pk, table};
+
+    fn user() -> TableSchema {
+        table(vec![pk("id"), column("name")])
+    }
+
+    #[test]
+    fn on_conflict_do_update_numbers_params_after_values() {
+        let builder = InsertBuilder::new(user())
+            .set(pair("id", 1i64))
+            .set(pair("name", "a".to_string()))
+            .on_conflict(vec!["id"])
+            .do_update(pair("name", "b".to_string()));
+        let (sql, params) = builder.to_sql().unwrap();
+        assert_eq!(sql, r#"insert into "public"."user" ("id","name") values ($1,$2) on conflict ("id") do update set "name"=$3"#);
+        assert_eq!(params.len(), 3);
+        assert_eq!(params[2], Value::from("b".to_string()));
+    }
+
+    #[test]
+    fn on_conflict_do_update_excluded() {
+        let builder = InsertBuilder::new(user())
+            .set(pair("id", 1i64))
+            .set(pair("name", "a".to_string()))
+            .on_conflict(vec!["id"])
+            .do_update_excluded(vec!["name"]);
+        let (sql, _) = builder.to_sql().unwrap();
+        assert_eq!(sql, r#"insert into "public"."user" ("id","name") values ($1,$2) on conflict ("id") do update set "name"=excluded."name""#);
+    }
+
+    #[test]
+    fn on_conflict_do_nothing_without_target() {
+        let builder = InsertBuilder::new(user()).set(pair("id", 1i64)).do_nothing();
+        let (sql, _) = builder.to_sql().unwrap();
+        assert_eq!(sql, r#"insert into "public"."user" ("id") values ($1) on conflict do nothing"#);
+    }
+
+    #[test]
+    fn do_update_without_target_is_rejected() {
+        let builder = InsertBuilder::new(user())
+            .set(pair("id", 1i64))
+            .do_update(pair("name", "b".to_string()));
+        assert!(matches!(builder.to_sql(), Err(sqlx::Error::Configuration(_))));
+
+        let builder = InsertBuilder::new(user())
+            .set(pair("id", 1i64))
+            .do_update_excluded(vec!["name"]);
+        assert!(matches!(builder.to_sql(), Err(sqlx::Error::Configuration(_))));
+    }
+
+    #[test]
+    fn rows_are_split_by_bind_param_limit() {
+        let rows = (0..65535i64)
+            .map(|n| vec![pair("id", n), pair("name", n.to_string())])
+            .collect();
+        let builder = InsertBuilder::new(user()).rows(rows);
+        let statements = builder.statements().unwrap();
+        let counts: Vec<usize> = statements.iter().map(|(_, params)| params.len()).collect();
+        assert_eq!(counts, vec![65534, 65534, 2]);
+        assert_eq!(
+            statements[2].0,
+            r#"insert into "public"."user" ("id","name") values ($1,$2)"#
+        );
+        assert_eq!(statements[1].1[0], Value::from(32767i64));
+        assert!(matches!(builder.to_sql(), Err(sqlx::Error::Configuration(_))));
+        assert!(matches!(builder.prepare(), Err(sqlx::Error::Configuration(_))));
+    }
+
+    #[test]
+    fn conflict_params_are_reserved_in_each_chunk() {
+        let rows = (0..32768i64)
+            .map(|n| vec![pair("id", n), pair("name", n.to_string())])
+            .collect();
+        let builder = InsertBuilder::new(user())
+            .rows(rows)
+            .on_conflict(vec!["id"])
+            .do_update(pair("name", "b".to_string()));
+        let statements = builder.statements().unwrap();
+        let counts: Vec<usize> = statements.iter().map(|(_, params)| params.len()).collect();
+        assert_eq!(counts, vec![65535, 3]);
+        assert!(statements[1].0.ends_with(r#"do update set "name"=$3"#));
+    }
 
-        query.execute(conn).await
+    #[test]
+    fn rows_without_data_render_no_statement() {
+        let builder = InsertBuilder::new(user()).rows(Vec::<Vec<Pair>>::new());
+        assert!(builder.statements().unwrap().is_empty());
+        assert!(matches!(builder.to_sql(), Err(sqlx::Error::Configuration(_))));
     }
 }
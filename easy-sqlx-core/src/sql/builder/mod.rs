@@ -3,4 +3,9 @@ pub mod insert_builder;
 pub mod update_builder;
 pub mod delete_builder;
 pub mod select_builder;
+pub mod copy_builder;
+pub mod error;
+pub mod statement;
+#[cfg(test)]
+pub(crate) mod test_schema;
 // pub mod easy_insert_builder;
\ No newline at end of file
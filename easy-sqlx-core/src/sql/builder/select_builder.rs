@@ -1,30 +1,62 @@
+use std::{borrow::Cow, marker::PhantomData};
+
 use crate::sql::{
     dialects::{
-        condition::{Condition, Where, WhereAppend},
-        page::{Order, PageRequest, PageResult},
+        condition::{Condition, Operator, Where, WhereAppend},
+        distinct::Distinct,
+        lock::{LockStrength, LockWait, RowLock},
+        page::{
+            decode_cursor, encode_cursor, CursorRequest, CursorResult, Order, OrderType,
+            PageRequest, PageResult,
+        },
         schema::{self, schema::Schema},
     },
-    schema::table::TableSchema,
+    entity::Entity,
+    schema::{column::Column, table::TableSchema},
+    typed::{EntityArg, Typed},
+    utils::{pair::Pair, value::Value},
 };
 
-use super::builder::QueryBuilder;
+use super::{builder::QueryBuilder, statement::Statement};
+use futures::{Stream, TryStreamExt as _};
 use sqlx::{Database, FromRow};
 
 #[derive(Debug)]
-pub struct SelectBuilder<'a> {
-    table: TableSchema,
+pub struct SelectBuilder<'a, T = ()> {
+    table: Cow<'a, TableSchema>,
     default_schema: &'a str,
     wh: Option<Where>,
     orders: Vec<Order>,
+    lock: Option<RowLock>,
+    distinct: Option<Distinct>,
+    with_deleted: bool,
+    entity: PhantomData<fn() -> T>,
 }
 
 impl<'a> SelectBuilder<'a> {
-    pub fn new(table: TableSchema) -> Self {
+    pub fn new<T: Into<Cow<'a, TableSchema>>>(table: T) -> Self {
+        Self::create(table.into())
+    }
+}
+
+impl<'a, T: Entity> SelectBuilder<'a, T> {
+    /// 实体 T 的查询，条件及排序只接受属于 T 的带类型列，如 User::ID.desc()
+    pub fn for_entity() -> Self {
+        Self::create(T::table_schema().into())
+    }
+}
+
+impl<'a, T> SelectBuilder<'a, T> {
+    fn create(table: Cow<'a, TableSchema>) -> Self {
         Self {
             table,
             default_schema: "",
             wh: None,
             orders: vec![],
+            lock: None,
+            distinct: None,
+            with_deleted: false,
+            entity: PhantomData,
         }
     }
 
@@ -33,20 +65,141 @@ impl<'a> SelectBuilder<'a> {
         self
     }
 
-    pub fn order_by(mut self, item: Order) -> Self {
-        self.orders.push(item);
+    pub fn order_by<O: EntityArg<T, Order>>(mut self, item: O) -> Self {
+        self.orders.push(item.into_arg());
+        self
+    }
+
+    /// select distinct，对整行去重
+    pub fn distinct(mut self) -> Self {
+        self.distinct = Some(Distinct::All);
+        self
+    }
+
+    /// select distinct on (...)，每组只保留排序后的第一行
+    /// 生成 sql 时 distinct on 的字段会排在 order by 的最前面
+    /// 例如查询每个用户最新的一条记录:
+    /// .distinct_on(vec!["user_id"]).order_by(Order::desc("create_at".to_string()))
+    pub fn distinct_on<S: Into<String>>(mut self, cols: Vec<S>) -> Self {
+        self.distinct = Some(Distinct::On(cols.into_iter().map(|c| c.into()).collect()));
+        self
+    }
+
+    /// 包含已软删除的记录，表未设置 soft_delete 时无影响
+    pub fn with_deleted(mut self) -> Self {
+        self.with_deleted = true;
+        self
+    }
+
+    /// 查询使用的条件，设置了软删除的表追加 "deleted_at" is null
+    /// 追加的条件不绑定参数，不影响已有条件的参数顺序
+    fn query_where(&self) -> Option<Where> {
+        let col = match &self.table.soft_delete {
+            Some(col) if !self.with_deleted => col,
+            _ => return self.wh.clone(),
+        };
+        let cond = Condition::column_expr(col.clone(), Operator::IsNull, "");
+        Some(match self.wh.clone() {
+            Some(w) => w.and(cond),
+            None => Where::new(cond),
+        })
+    }
+
+    /// 设置行锁强度，保留已设置的等待方式
+    fn lock(mut self, strength: LockStrength) -> Self {
+        if let Some(lock) = self.lock.as_mut() {
+            lock.strength = strength;
+        } else {
+            self.lock = Some(RowLock::new(strength));
+        }
+        self
+    }
+
+    /// 设置等待方式，未设置行锁时使用 for update
+    fn lock_wait(mut self, wait: LockWait) -> Self {
+        self.lock
+            .get_or_insert_with(|| RowLock::new(LockStrength::Update))
+            .wait = wait;
         self
     }
 
+    /// postgres 不允许行锁与 distinct 同时使用，执行前检查避免数据库报错
+    fn check_lock(&self) -> Result<(), sqlx::Error> {
+        if self.lock.is_some() && self.distinct.is_some() {
+            return Err(sqlx::Error::Configuration(
+                "row lock (for update / for share) can not be used with distinct".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// for update
+    pub fn for_update(self) -> Self {
+        self.lock(LockStrength::Update)
+    }
+
+    /// for no key update
+    pub fn for_no_key_update(self) -> Self {
+        self.lock(LockStrength::NoKeyUpdate)
+    }
+
+    /// for share
+    pub fn for_share(self) -> Self {
+        self.lock(LockStrength::Share)
+    }
+
+    /// for key share
+    pub fn for_key_share(self) -> Self {
+        self.lock(LockStrength::KeyShare)
+    }
+
+    /// nowait，行已被锁定时立即报错
+    pub fn nowait(self) -> Self {
+        self.lock_wait(LockWait::NoWait)
+    }
+
+    /// skip locked，跳过已被锁定的行，适用于任务队列领取任务
+    pub fn skip_locked(self) -> Self {
+        self.lock_wait(LockWait::SkipLocked)
+    }
+
+    /// 生成查询的 sql 及按顺序绑定的参数，不执行查询
+    /// 可用于测试生成的 sql 或记录带参数的日志
+    pub fn to_sql(&self) -> (String, Vec<Value>) {
+        let params = self.query_where().map(|w| w.params()).unwrap_or_default();
+        (self.generate_query_as(), params)
+    }
+
+    /// 生成可重复执行的语句，参数可以重新设置
+    /// 与执行查询相同，行锁与 distinct 同时使用时返回错误
+    pub fn prepare(&self) -> Result<Statement, sqlx::Error> {
+        self.check_lock()?;
+        Ok(self.to_sql().into())
+    }
+
     fn generate_query_as(&self) -> String {
         let schema = schema::new(self.default_schema.to_string());
-        let sql = schema.sql_select(&self.table, self.wh.clone(), &self.orders, None);
+        let sql = schema.sql_select(
+            &self.table,
+            self.query_where(),
+            &self.orders,
+            None,
+            self.lock.as_ref(),
+            self.distinct.as_ref(),
+        );
         sql
     }
 
     fn generate_page_query_as(&self, pg: &PageRequest) -> String {
         let schema = schema::new(self.default_schema.to_string());
-        let sql = schema.sql_select(&self.table, self.wh.clone(), &self.orders, Some(pg));
+        let sql = schema.sql_select(
+            &self.table,
+            self.query_where(),
+            &self.orders,
+            Some(pg),
+            self.lock.as_ref(),
+            self.distinct.as_ref(),
+        );
         sql
     }
 
@@ -55,9 +208,11 @@ impl<'a> SelectBuilder<'a> {
         let sql = schema.sql_select_columns(
             &self.table,
             &vec![field.to_string()],
-            self.wh.clone(),
+            self.query_where(),
             &self.orders,
             None,
+            self.lock.as_ref(),
+            self.distinct.as_ref(),
         );
         sql
     }
@@ -67,12 +222,124 @@ impl<'a> SelectBuilder<'a> {
         let sql = schema.sql_select_columns(
             &self.table,
             &vec![field.to_string()],
-            self.wh.clone(),
+            self.query_where(),
             &self.orders,
             Some(pg),
+            self.lock.as_ref(),
+            self.distinct.as_ref(),
         );
         sql
     }
+
+    /// 游标分页使用的排序，未设置排序时按主键升序
+    fn cursor_orders(&self) -> Vec<Order> {
+        if !self.orders.is_empty() {
+            return self.orders.clone();
+        }
+        self.table
+            .columns
+            .iter()
+            .filter(|c| c.pk)
+            .map(|c| Order::asc(c.name.clone()))
+            .collect()
+    }
+
+    /// 查找排序字段对应的列，排序字段可以是结构体字段名称或数据库列名称
+    fn find_order_column(&self, order: &Order) -> Result<&Column, sqlx::Error> {
+        self.table
+            .columns
+            .iter()
+            .find(|c| c.name == order.field || c.get_column_name() == order.field)
+            .ok_or_else(|| sqlx::Error::ColumnNotFound(order.field.clone()))
+    }
+
+    /// 校验游标分页的排序并查找排序列
+    /// 游标条件使用 > / < 比较，无法定位空值，因此不支持 nulls first / nulls last
+    fn cursor_columns(&self) -> Result<(Vec<Order>, Vec<&Column>), sqlx::Error> {
+        self.check_lock()?;
+        if let Some(Distinct::On(_)) = &self.distinct {
+            return Err(sqlx::Error::Configuration(
+                "cursor pagination does not support distinct on".into(),
+            ));
+        }
+        let orders = self.cursor_orders();
+        if orders.iter().any(|o| o.expr) {
+            return Err(sqlx::Error::Configuration(
+                "cursor pagination does not support expression orders".into(),
+            ));
+        }
+        if let Some(order) = orders.iter().find(|o| o.nulls.is_some()) {
+            return Err(sqlx::Error::Configuration(
+                format!(
+                    "cursor pagination does not support nulls ordering on {}",
+                    order.field
+                )
+                .into(),
+            ));
+        }
+        if orders.is_empty() {
+            return Err(sqlx::Error::Configuration(
+                format!(
+                    "cursor pagination on table {} requires orders or primary key",
+                    self.table.name
+                )
+                .into(),
+            ));
+        }
+        let columns = orders
+            .iter()
+            .map(|o| self.find_order_column(o))
+            .collect::<Result<Vec<&Column>, sqlx::Error>>()?;
+        Ok((orders, columns))
+    }
+
+    /// 下一页游标的值不能为空，排序列为空时无法生成游标
+    fn check_cursor_values(columns: &[&Column], values: &[Value]) -> Result<(), sqlx::Error> {
+        match columns.iter().zip(values).find(|(_, v)| v.is_null()) {
+            Some((column, _)) => Err(sqlx::Error::Configuration(
+                format!(
+                    "cursor pagination does not support null value in order column {}",
+                    column.name
+                )
+                .into(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// 生成游标定位条件
+    /// (a > $1) or (a = $1 and b > $2) or ...，降序的列使用 <
+    fn cursor_condition(columns: &[&Column], orders: &[Order], values: Vec<Value>) -> Condition {
+        let mut cond: Option<Condition> = None;
+        for (n, order) in orders.iter().enumerate() {
+            let op = match order.order_type {
+                OrderType::Desc => Operator::Lt,
+                _ => Operator::Gt,
+            };
+            let mut item = Condition::Condition(
+                Pair {
+                    name: columns[n].get_column_name(),
+                    value: values[n].clone(),
+                },
+                op,
+            );
+            for m in (0..n).rev() {
+                let eq = Condition::Condition(
+                    Pair {
+                        name: columns[m].get_column_name(),
+                        value: values[m].clone(),
+                    },
+                    Operator::Eq,
+                );
+                item = Condition::And(Box::new(eq), Box::new(item));
+            }
+            cond = Some(match cond {
+                Some(c) => Condition::Or(Box::new(c), Box::new(item)),
+                None => item,
+            });
+        }
+        cond.unwrap_or_else(|| Condition::raw("1 = 1", vec![]))
+    }
 }
 impl<'a> WhereAppend<Condition> for SelectBuilder<'a> {
     fn and(mut self, cond: Condition) -> Self {
@@ -114,10 +381,32 @@ impl<'a> WhereAppend<Where> for SelectBuilder<'a> {
     }
 }
 
+impl<'a, T> WhereAppend<Typed<T, Condition>> for SelectBuilder<'a, T> {
+    fn and(mut self, cond: Typed<T, Condition>) -> Self {
+        let cond = cond.into_inner();
+        if let Some(w) = self.wh {
+            self.wh = Some(w.and(cond));
+        } else {
+            self.wh = Some(Where::new(cond));
+        }
+        self
+    }
+
+    fn or(mut self, cond: Typed<T, Condition>) -> Self {
+        let cond = cond.into_inner();
+        if let Some(w) = self.wh {
+            self.wh = Some(w.or(cond));
+        } else {
+            self.wh = Some(Where::new(cond));
+        }
+        self
+    }
+}
+
 #[cfg(feature = "postgres")]
 use sqlx::Postgres;
 
-impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
+impl<'a, T> QueryBuilder<'a> for SelectBuilder<'a, T> {
     #[cfg(feature = "postgres")]
     type DB = Postgres;
 
@@ -129,9 +418,10 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         O: std::marker::Send,
         O: Unpin,
     {
+        self.check_lock()?;
         let sql = self.generate_query_as();
         let mut query = sqlx::query_as::<Self::DB, O>(&sql);
-        if let Some(w) = &self.wh {
+        if let Some(w) = &self.query_where() {
             query = w.bind_to_query_as(query);
         }
 
@@ -150,9 +440,10 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         O: Unpin,
         for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
     {
+        self.check_lock()?;
         let sql = self.generate_query_as();
         let mut query = sqlx::query_as(&sql);
-        if let Some(w) = &self.wh {
+        if let Some(w) = &self.query_where() {
             query = w.bind_to_query_as(query);
         }
 
@@ -169,9 +460,10 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         O: std::marker::Send,
         O: Unpin,
     {
+        self.check_lock()?;
         let sql = self.generate_query_as();
         let mut query = sqlx::query_as(&sql);
-        if let Some(w) = &self.wh {
+        if let Some(w) = &self.query_where() {
             query = w.bind_to_query_as(query);
         }
 
@@ -180,6 +472,35 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         Ok(result)
     }
 
+    fn stream<'e, 'c: 'e, E, O>(
+        self,
+        executor: E,
+    ) -> impl Stream<Item = Result<O, sqlx::Error>> + 'e
+    where
+        E: 'e + sqlx::Executor<'c, Database = Self::DB>,
+        for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
+        O: 'e,
+        O: std::marker::Send,
+        O: Unpin,
+    {
+        let check = self.check_lock();
+        let sql = self.generate_query_as();
+        let wh = self.query_where();
+        sqlx_core::try_stream! {
+            check?;
+            let mut query = sqlx::query_as::<Self::DB, O>(&sql);
+            if let Some(w) = &wh {
+                query = w.bind_to_query_as(query);
+            }
+
+            let mut rows = query.fetch(executor);
+            while let Some(row) = rows.try_next().await? {
+                r#yield!(row);
+            }
+            Ok(())
+        }
+    }
+
     async fn page<'e, 'c: 'e, E, O>(
         &self,
         executor: E,
@@ -192,6 +513,7 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         O: std::marker::Send,
         O: Unpin,
     {
+        self.check_lock()?;
         let mut result: PageResult<O> = PageResult {
             records: vec![],
             page_count: 0,
@@ -203,13 +525,15 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
             // 查询总条数，统计页面信息
             let mut counter = SelectBuilder::new(self.table.clone());
             counter.wh = self.wh.clone();
+            counter.distinct = self.distinct.clone();
+            counter.with_deleted = self.with_deleted;
             let total = counter.count(executor).await?;
             result.set_total(total);
         }
 
         let sql = self.generate_page_query_as(page);
         let mut query = sqlx::query_as(&sql);
-        if let Some(w) = &self.wh {
+        if let Some(w) = &self.query_where() {
             query = w.bind_to_query_as(query);
         }
 
@@ -218,6 +542,72 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         Ok(result)
     }
 
+    async fn cursor<'e, 'c: 'e, E, O>(
+        &self,
+        executor: E,
+        req: &CursorRequest,
+    ) -> Result<CursorResult<O>, sqlx::Error>
+    where
+        E: 'e + sqlx::Executor<'c, Database = Self::DB>,
+        for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
+        O: 'e,
+        O: std::marker::Send,
+        O: Unpin,
+    {
+        let (orders, columns) = self.cursor_columns()?;
+
+        let mut wh = self.query_where();
+        if let Some(cursor) = req.get_cursor() {
+            // 从游标位置之后开始查询
+            let values = decode_cursor(cursor)
+                .filter(|values| values.len() == columns.len())
+                .filter(|values| !values.iter().any(|v| v.is_null()))
+                .ok_or_else(|| sqlx::Error::Decode(format!("invalid cursor: {cursor}").into()))?;
+            let cond = Self::cursor_condition(&columns, &orders, values);
+            wh = Some(match wh {
+                Some(w) => w.and(cond),
+                None => Where::new(cond),
+            });
+        }
+
+        // 多查询一条记录，用于判断是否还有下一页
+        let size = req.get_size();
+        let schema = schema::new(self.default_schema.to_string());
+        let sql = schema.sql_select(
+            &self.table,
+            wh.clone(),
+            &orders,
+            Some(&PageRequest::new(size + 1, 1)),
+            self.lock.as_ref(),
+            self.distinct.as_ref(),
+        );
+        let mut query = sqlx::query::<Self::DB>(&sql);
+        if let Some(w) = &wh {
+            query = w.bind_to_query(query);
+        }
+        let rows = query.fetch_all(executor).await?;
+
+        let mut result: CursorResult<O> = CursorResult {
+            size,
+            next_cursor: None,
+            records: vec![],
+        };
+        if rows.len() > size {
+            let last = &rows[size - 1];
+            let values = columns
+                .iter()
+                .map(|c| Value::from_pg_row(last, &c.name))
+                .collect::<Result<Vec<Value>, sqlx::Error>>()?;
+            Self::check_cursor_values(&columns, &values)?;
+            result.next_cursor = Some(encode_cursor(&values));
+        }
+        for row in rows.iter().take(size) {
+            result.records.push(O::from_row(row)?);
+        }
+
+        Ok(result)
+    }
+
     // async fn fetch_one_scalar<'q>(&self, field: &'q str) -> Result<O, Error>
     // where
     //     (O,): for<'r> FromRow<'r, <Self::DB as Database>::Row>,
@@ -246,9 +636,10 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         E: 'c + sqlx::Executor<'c, Database = Self::DB>,
         O: Send + Unpin,
     {
+        self.check_lock()?;
         let sql = self.generate_query_scalar(field);
         let mut query = sqlx::query_scalar(&sql);
-        if let Some(w) = &self.wh {
+        if let Some(w) = &self.query_where() {
             query = w.bind_to_query_scalar(query);
         }
         query.fetch_one(executor).await
@@ -264,9 +655,10 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         E: 'c + sqlx::Executor<'c, Database = Self::DB>,
         O: Send + Unpin,
     {
+        self.check_lock()?;
         let sql = self.generate_query_scalar(field);
         let mut query = sqlx::query_scalar(&sql);
-        if let Some(w) = &self.wh {
+        if let Some(w) = &self.query_where() {
             query = w.bind_to_query_scalar(query);
         }
         query.fetch_optional(executor).await
@@ -282,9 +674,10 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         E: 'c + sqlx::Executor<'c, Database = Self::DB>,
         O: Send + Unpin,
     {
+        self.check_lock()?;
         let sql = self.generate_query_scalar(field);
         let mut query = sqlx::query_scalar(&sql);
-        if let Some(w) = &self.wh {
+        if let Some(w) = &self.query_where() {
             query = w.bind_to_query_scalar(query);
         }
         query.fetch_all(executor).await
@@ -294,10 +687,11 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
     where
         E: 'c + sqlx::Executor<'c, Database = Self::DB>,
     {
+        self.check_lock()?;
         let schema = schema::new(self.default_schema.to_string());
-        let sql = schema.sql_count(&self.table, self.wh.clone());
+        let sql = schema.sql_count(&self.table, self.query_where(), self.distinct.as_ref());
         let mut query = sqlx::query_scalar(&sql);
-        if let Some(w) = &self.wh {
+        if let Some(w) = &self.query_where() {
             query = w.bind_to_query_scalar(query);
         }
 
@@ -316,6 +710,7 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         E: 'e + sqlx::Executor<'c, Database = Self::DB> + 'c + Copy,
         O: Send + Unpin,
     {
+        self.check_lock()?;
         let mut result: PageResult<O> = PageResult {
             records: vec![],
             page_count: 0,
@@ -327,13 +722,15 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
             // 查询总条数，统计页面信息
             let mut counter = SelectBuilder::new(self.table.clone());
             counter.wh = self.wh.clone();
+            counter.distinct = self.distinct.clone();
+            counter.with_deleted = self.with_deleted;
             let total = counter.count(executor).await?;
             result.set_total(total);
         }
 
         let sql = self.generate_query_page_scalar(field, page);
         let mut query = sqlx::query_scalar(&sql);
-        if let Some(w) = &self.wh {
+        if let Some(w) = &self.query_where() {
             query = w.bind_to_query_scalar(query);
         }
         result.records = query.fetch_all(executor).await?;
@@ -341,3 +738,101 @@ impl<'a> QueryBuilder<'a> for SelectBuilder<'a> {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::builder::test_schema::{column, cond, pk, table};
+
+    #[test]
+    fn lock_with_distinct_is_rejected() {
+        let user = table(vec![pk("id"), column("name")]);
+        let builder = SelectBuilder::new(user.clone()).distinct().for_update();
+        assert!(matches!(
+            builder.check_lock(),
+            Err(sqlx::Error::Configuration(_))
+        ));
+        assert!(SelectBuilder::new(user).for_update().check_lock().is_ok());
+    }
+
+    #[test]
+    fn prepare_checks_lock() {
+        let user = table(vec![pk("id"), column("name")]);
+        let builder = SelectBuilder::new(user.clone()).distinct().for_update();
+        assert!(matches!(builder.prepare(), Err(sqlx::Error::Configuration(_))));
+        assert!(SelectBuilder::new(user).for_update().prepare().is_ok());
+    }
+
+    #[test]
+    fn cursor_rejects_nulls_ordering() {
+        let user = table(vec![pk("id"), column("name")]);
+        let builder =
+            SelectBuilder::new(user.clone()).order_by(Order::asc("name".to_string()).nulls_last());
+        assert!(matches!(
+            builder.cursor_columns(),
+            Err(sqlx::Error::Configuration(_))
+        ));
+
+        let builder = SelectBuilder::new(user).order_by(Order::asc("name".to_string()));
+        let (orders, columns) = builder.cursor_columns().unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(columns[0].name, "name");
+    }
+
+    #[test]
+    fn cursor_rejects_null_values() {
+        let name = column("name");
+        let id = pk("id");
+        let columns = vec![&name, &id];
+        let values = vec![Value::Text(None), Value::from(1i64)];
+        assert!(matches!(
+            SelectBuilder::<()>::check_cursor_values(&columns, &values),
+            Err(sqlx::Error::Configuration(_))
+        ));
+        let values = vec![Value::Text(Some("a".to_string())), Value::from(1i64)];
+        assert!(SelectBuilder::<()>::check_cursor_values(&columns, &values).is_ok());
+    }
+
+    fn soft_deleted() -> TableSchema {
+        TableSchema {
+            soft_delete: Some("deleted_at".to_string()),
+            ..table(vec![
+                pk("id"),
+                Column {
+                    nullable: true,
+                    ..column("deleted_at")
+                },
+            ])
+        }
+    }
+
+    #[test]
+    fn soft_deleted_rows_are_filtered() {
+        let (sql, params) = SelectBuilder::new(soft_deleted()).to_sql();
+        assert_eq!(
+            sql,
+            r#"select "id","deleted_at" from "public"."user"  where "deleted_at" is null  "#
+        );
+        assert!(params.is_empty());
+
+        let builder = SelectBuilder::new(soft_deleted()).and(cond("id", Operator::Gt, 1i64));
+        let (sql, params) = builder.to_sql();
+        assert_eq!(
+            sql,
+            r#"select "id","deleted_at" from "public"."user"  where "id" > $1 and "deleted_at" is null  "#
+        );
+        assert_eq!(params, vec![Value::from(1i64)]);
+    }
+
+    #[test]
+    fn with_deleted_keeps_soft_deleted_rows() {
+        let builder = SelectBuilder::new(soft_deleted())
+            .and(cond("id", Operator::Gt, 1i64))
+            .with_deleted();
+        let (sql, _) = builder.to_sql();
+        assert_eq!(
+            sql,
+            r#"select "id","deleted_at" from "public"."user"  where "id" > $1  "#
+        );
+    }
+}
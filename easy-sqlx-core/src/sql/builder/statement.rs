@@ -0,0 +1,136 @@
+use sqlx::{Error, Executor, FromRow};
+
+use crate::sql::utils::value::Value;
+
+/// 预先生成的语句，sql 只生成一次，参数可以重新设置后重复执行
+/// 由各个 builder 的 prepare 生成，sqlx 会在连接上按 sql 缓存服务端预处理语句
+/// ```rust,ignore
+/// let mut stmt = User::select().and(User::id_eq(0)).prepare()?;
+/// for id in ids {
+///     stmt.set_param(1, id)?;
+///     let user: User = stmt.fetch_one(&mut conn).await?;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Statement {
+    sql: String,
+    params: Vec<Value>,
+}
+
+impl Statement {
+    pub fn new(sql: String, params: Vec<Value>) -> Self {
+        Self { sql, params }
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// 按占位符顺序排列的参数
+    pub fn params(&self) -> &[Value] {
+        &self.params
+    }
+
+    /// 重新设置第 index 个参数，index 从 1 开始，与 sql 中的 $index 对应
+    pub fn set_param<V: Into<Value>>(&mut self, index: usize, value: V) -> Result<(), Error> {
+        let count = self.params.len();
+        let param = index
+            .checked_sub(1)
+            .and_then(|n| self.params.get_mut(n))
+            .ok_or_else(|| {
+                Error::Configuration(
+                    format!("parameter ${index} out of range, statement has {count} parameters")
+                        .into(),
+                )
+            })?;
+        *param = value.into();
+        Ok(())
+    }
+
+    /// 重新设置全部参数，参数个数必须与 sql 中的占位符个数一致
+    pub fn set_params(&mut self, params: Vec<Value>) -> Result<(), Error> {
+        if params.len() != self.params.len() {
+            return Err(Error::Configuration(
+                format!(
+                    "statement requires {} parameters, got {}",
+                    self.params.len(),
+                    params.len()
+                )
+                .into(),
+            ));
+        }
+        self.params = params;
+        Ok(())
+    }
+}
+
+impl From<(String, Vec<Value>)> for Statement {
+    fn from((sql, params): (String, Vec<Value>)) -> Self {
+        Self::new(sql, params)
+    }
+}
+
+#[cfg(feature = "postgres")]
+use sqlx::{
+    postgres::{PgQueryResult, PgRow},
+    Execute as _, Postgres,
+};
+
+#[cfg(feature = "postgres")]
+impl Statement {
+    pub async fn execute<C>(&self, conn: &mut C) -> Result<PgQueryResult, Error>
+    where
+        for<'e> &'e mut C: Executor<'e, Database = Postgres>,
+    {
+        let mut query = sqlx::query::<Postgres>(&self.sql);
+        for param in &self.params {
+            query = param.bind_to_query(query);
+        }
+
+        tracing::debug!("easy-sqlx: {}", query.sql());
+
+        query.execute(conn).await
+    }
+
+    /// 获取一条记录
+    pub async fn fetch_one<'c, E, O>(&self, executor: E) -> Result<O, Error>
+    where
+        E: 'c + Executor<'c, Database = Postgres>,
+        for<'r> O: FromRow<'r, PgRow>,
+        O: Send + Unpin,
+    {
+        let mut query = sqlx::query_as::<Postgres, O>(&self.sql);
+        for param in &self.params {
+            query = param.bind_to_query_as(query);
+        }
+        query.fetch_one(executor).await
+    }
+
+    /// 获取一条记录，如果不存在返回 None
+    pub async fn fetch_optional<'c, E, O>(&self, executor: E) -> Result<Option<O>, Error>
+    where
+        E: 'c + Executor<'c, Database = Postgres>,
+        for<'r> O: FromRow<'r, PgRow>,
+        O: Send + Unpin,
+    {
+        let mut query = sqlx::query_as::<Postgres, O>(&self.sql);
+        for param in &self.params {
+            query = param.bind_to_query_as(query);
+        }
+        query.fetch_optional(executor).await
+    }
+
+    /// 获取全部记录
+    pub async fn fetch_all<'c, E, O>(&self, executor: E) -> Result<Vec<O>, Error>
+    where
+        E: 'c + Executor<'c, Database = Postgres>,
+        for<'r> O: FromRow<'r, PgRow>,
+        O: Send + Unpin,
+    {
+        let mut query = sqlx::query_as::<Postgres, O>(&self.sql);
+        for param in &self.params {
+            query = param.bind_to_query_as(query);
+        }
+        query.fetch_all(executor).await
+    }
+}
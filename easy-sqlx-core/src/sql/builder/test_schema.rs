@@ -0,0 +1,44 @@
+//! builder 测试共用的表结构、赋值及条件
+
+use crate::sql::{
+    dialects::condition::{Condition, Operator},
+    schema::{column::Column, table::TableSchema},
+    utils::{pair::Pair, value::Value},
+};
+
+/// 表 "user"，生成的 sql 为 "public"."user"
+pub(crate) fn table(columns: Vec<Column>) -> TableSchema {
+    TableSchema {
+        name: "user".to_string(),
+        columns,
+        ..Default::default()
+    }
+}
+
+/// 字段名称与数据库列名称相同的列
+pub(crate) fn column(name: &str) -> Column {
+    Column {
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+/// 主键列
+pub(crate) fn pk(name: &str) -> Column {
+    Column {
+        pk: true,
+        ..column(name)
+    }
+}
+
+pub(crate) fn pair(name: &str, value: impl Into<Value>) -> Pair {
+    Pair {
+        name: name.to_string(),
+        value: value.into(),
+    }
+}
+
+/// 列 op 值
+pub(crate) fn cond(name: &str, op: Operator, value: impl Into<Value>) -> Condition {
+    Condition::Condition(pair(name, value), op)
+}
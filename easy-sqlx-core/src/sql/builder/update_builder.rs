@@ -1,27 +1,62 @@
+use std::{borrow::Cow, marker::PhantomData};
+
 use crate::sql::{
     dialects::{condition::{Condition, Where, WhereAppend}, schema::{self, schema::Schema}},
+    entity::Entity,
     schema::table::TableSchema,
-    utils::pair::Pair,
+    typed::{EntityArg, Typed},
+    utils::{
+        pair::{Pair, SetExpr},
+        value::Value,
+    },
 };
 
-use sqlx::{Database, Execute as _};
-use super::builder::ExecuteBuilder;
+use sqlx::{Database, Execute as _, FromRow};
+use super::{builder::ExecuteBuilder, error::BuilderError, statement::Statement};
 
 #[derive(Debug)]
-pub struct UpdateBuilder<'a> {
-    table: TableSchema,
+pub struct UpdateBuilder<'a, T = ()> {
+    table: Cow<'a, TableSchema>,
     default_schema: &'a str,
     columns: Vec<Pair>,
+    exprs: Vec<SetExpr>,
     wh: Option<Where>,
+    returning: Vec<String>,
+    /// 允许没有 where 条件时操作全部记录
+    all_rows: bool,
+    /// 乐观锁检查，没有更新任何记录时返回 BuilderError::StaleVersion
+    check_version: bool,
+    /// 没有设置任何列时执行直接返回，不访问数据库
+    skip_empty: bool,
+    entity: PhantomData<fn() -> T>,
 }
 
 impl<'a> UpdateBuilder<'a> {
-    pub fn new(table: TableSchema) -> Self {
+    pub fn new<T: Into<Cow<'a, TableSchema>>>(table: T) -> Self {
+        Self::create(table.into())
+    }
+}
+
+impl<'a, T: Entity> UpdateBuilder<'a, T> {
+    /// 实体 T 的更新，赋值及条件只接受属于 T 的带类型列，如 User::NAME.set(name)
+    pub fn for_entity() -> Self {
+        Self::create(T::table_schema().into())
+    }
+}
+
+impl<'a, T> UpdateBuilder<'a, T> {
+    fn create(table: Cow<'a, TableSchema>) -> Self {
         Self {
             table,
             default_schema: "",
             columns: vec![],
+            exprs: vec![],
             wh: None,
+            returning: vec![],
+            all_rows: false,
+            check_version: false,
+            skip_empty: false,
+            entity: PhantomData,
         }
     }
 
@@ -30,11 +65,164 @@ impl<'a> UpdateBuilder<'a> {
         self
     }
 
-    pub fn set(mut self, pair: Pair) -> Self {
-        self.columns.push(pair);
+    /// 设置更新的列
+    pub fn set<P: EntityArg<T, Pair>>(mut self, pair: P) -> Self {
+        self.columns.push(pair.into_arg());
         // self.r#where()
         self
     }
+
+    /// 以 sql 表达式设置更新的列，如 "count" = coalesce("count", 0) + $1
+    pub fn set_expr<E: EntityArg<T, SetExpr>>(mut self, expr: E) -> Self {
+        self.exprs.push(expr.into_arg());
+        self
+    }
+
+    /// 乐观锁检查，没有更新任何记录时返回 BuilderError::StaleVersion
+    /// 需要同时设置版本条件，生成的 update 函数会自动设置
+    /// 错误以 sqlx::Error::Configuration 返回，使用 BuilderError::from_sqlx 判断
+    /// ```rust,ignore
+    /// match user.update().execute(&mut conn).await {
+    ///     Err(err) if matches!(BuilderError::from_sqlx(&err), Some(BuilderError::StaleVersion(_))) => {
+    ///         // 记录已被修改或已删除，重新加载后再更新
+    ///     }
+    ///     result => result?,
+    /// };
+    /// ```
+    pub fn check_version(mut self) -> Self {
+        self.check_version = true;
+        self
+    }
+
+    /// 检查乐观锁是否更新了记录
+    fn check_affected(&self, affected: u64) -> Result<(), sqlx::Error> {
+        if self.check_version && affected == 0 {
+            return Err(BuilderError::StaleVersion(self.table.name.clone()).into());
+        }
+        Ok(())
+    }
+
+    /// 没有设置任何列时 execute / execute_returning 直接返回空结果，不访问数据库
+    /// 未调用时没有设置列返回错误，prepare 没有可生成的语句，始终返回错误
+    pub fn skip_empty(mut self) -> Self {
+        self.skip_empty = true;
+        self
+    }
+
+    /// 是否有需要更新的列
+    fn has_sets(&self) -> bool {
+        !self.columns.is_empty() || !self.exprs.is_empty()
+    }
+
+    /// prepare 及执行前的检查: 必须设置更新的列，并且有 where 条件或调用了 all_rows
+    fn check(&self, sql: &str) -> Result<(), sqlx::Error> {
+        if !self.has_sets() {
+            return Err(sqlx::Error::Configuration(
+                format!("no columns to update in table {}", self.table.name).into(),
+            ));
+        }
+        self.check_condition(sql)
+    }
+
+    /// 调用了 skip_empty 并且没有设置列时不执行
+    fn skipped(&self) -> bool {
+        self.skip_empty && !self.has_sets()
+    }
+
+    /// 更新的列，表中有修改时间列并且没有手动设置时，追加修改时间为当前时间
+    fn set_columns(&self) -> Vec<Pair> {
+        let mut columns = self.columns.clone();
+        for col in self.table.columns.iter().filter(|c| c.updated_at) {
+            let name = col.get_column_name();
+            let setted = columns
+                .iter()
+                .map(|p| &p.name)
+                .chain(self.exprs.iter().map(|e| &e.name))
+                .any(|n| *n == name || *n == col.name);
+            if !setted {
+                columns.push(Pair {
+                    name,
+                    value: Value::now(),
+                });
+            }
+        }
+        columns
+    }
+
+    /// 确认修改全部记录，未调用时没有 where 条件的语句返回 BuilderError::Unconditioned
+    pub fn all_rows(mut self) -> Self {
+        self.all_rows = true;
+        self
+    }
+
+    /// 检查是否有 where 条件，没有条件并且未调用 all_rows 时拒绝执行
+    fn check_condition(&self, sql: &str) -> Result<(), sqlx::Error> {
+        let has_condition = self.wh.as_ref().is_some_and(|w| !w.is_empty());
+        if !has_condition && !self.all_rows {
+            tracing::error!("easy-sqlx: refused to execute without where condition: {sql}");
+            return Err(BuilderError::Unconditioned(sql.to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// returning 子句返回的列，使用 execute_returning 执行时生效
+    pub fn returning<S: Into<String>>(mut self, cols: Vec<S>) -> Self {
+        self.returning = cols.into_iter().map(|c| c.into()).collect();
+        self
+    }
+
+    /// returning 的列，未设置时返回所有列
+    fn returning_columns(&self) -> Vec<String> {
+        if self.returning.is_empty() {
+            self.table
+                .columns
+                .iter()
+                .map(|c| c.get_query_column_name())
+                .collect()
+        } else {
+            self.returning.clone()
+        }
+    }
+
+    /// 生成 execute 执行的 sql 及按顺序绑定的参数，不执行语句
+    /// returning 子句只在 execute_returning 执行时生成
+    pub fn to_sql(&self) -> (String, Vec<Value>) {
+        let columns = self.set_columns();
+        let sql = self.generate_sql(&columns, false);
+
+        let mut params = vec![];
+        for col in &columns {
+            col.value.append_params(&mut params);
+        }
+        for expr in &self.exprs {
+            for param in &expr.params {
+                param.append_params(&mut params);
+            }
+        }
+        if let Some(w) = &self.wh {
+            params.extend(w.params());
+        }
+        (sql, params)
+    }
+
+    /// 生成可重复执行的语句，参数可以重新设置
+    /// 修改时间等自动设置的参数在生成时确定，执行时不检查乐观锁
+    pub fn prepare(&self) -> Result<Statement, sqlx::Error> {
+        let (sql, params) = self.to_sql();
+        self.check(&sql)?;
+        Ok(Statement::new(sql, params))
+    }
+
+    fn generate_sql(&self, columns: &[Pair], returning: bool) -> String {
+        let schema = schema::new(self.default_schema.to_string());
+
+        let cols: Vec<String> = columns.iter().map(|c| c.name.to_string()).collect();
+        let mut sql = schema.sql_update_columns(&self.table, &cols, &self.exprs, self.wh.clone());
+        if returning {
+            sql.push_str(&schema.sql_returning(&self.returning_columns()));
+        }
+        sql
+    }
 }
 impl<'a> WhereAppend<Condition> for UpdateBuilder<'a> {
     fn and(mut self, cond: Condition) -> Self {
@@ -76,10 +264,32 @@ impl<'a> WhereAppend<Where> for UpdateBuilder<'a> {
     }
 }
 
+impl<'a, T> WhereAppend<Typed<T, Condition>> for UpdateBuilder<'a, T> {
+    fn and(mut self, cond: Typed<T, Condition>) -> Self {
+        let cond = cond.into_inner();
+        if let Some(w) = self.wh {
+            self.wh = Some(w.and(cond));
+        } else {
+            self.wh = Some(Where::new(cond));
+        }
+        self
+    }
+
+    fn or(mut self, cond: Typed<T, Condition>) -> Self {
+        let cond = cond.into_inner();
+        if let Some(w) = self.wh {
+            self.wh = Some(w.or(cond));
+        } else {
+            self.wh = Some(Where::new(cond));
+        }
+        self
+    }
+}
+
 #[cfg(feature = "postgres")]
 use sqlx::Postgres;
 
-impl<'a> ExecuteBuilder for UpdateBuilder<'a> {
+impl<'a, T> ExecuteBuilder for UpdateBuilder<'a, T> {
     #[cfg(feature = "postgres")]
     type DB = Postgres;
 
@@ -90,10 +300,14 @@ impl<'a> ExecuteBuilder for UpdateBuilder<'a> {
     where
         for<'e> &'e mut C: sqlx::Executor<'e, Database = Self::DB>,
     {
-        let schema = schema::new(self.default_schema.to_string());
-        
-        let cols: Vec<String> = self.columns.iter().map(|c| c.name.to_string()).collect();
-        let sql = schema.sql_update_columns(&self.table, &cols, self.wh.clone());
+        if self.skipped() {
+            // 没有需要更新的列
+            return Ok(<Self::DB as Database>::QueryResult::default());
+        }
+
+        let columns = self.set_columns();
+        let sql = self.generate_sql(&columns, false);
+        self.check(&sql)?;
         // if let Some(w) = &self.wh {
         //     let (ws, _) = w.sql(self.columns.len() + 1, &schema.quoter());
         //     if !ws.is_empty() { 
@@ -107,9 +321,15 @@ impl<'a> ExecuteBuilder for UpdateBuilder<'a> {
         let mut query: sqlx::query::Query<'_, Self::DB, <Self::DB as Database>::Arguments<'_>> =
             sqlx::query::<Self::DB>(&sql);
 
-        for col in &self.columns {
+        for col in &columns {
             query = col.value.bind_to_query(query);
         }
+
+        for expr in &self.exprs {
+            for param in &expr.params {
+                query = param.bind_to_query(query);
+            }
+        }
         
         if let Some(w) = &self.wh {
             query = w.bind_to_query(query);
@@ -117,6 +337,164 @@ impl<'a> ExecuteBuilder for UpdateBuilder<'a> {
 
         tracing::debug!("easy-sqlx: {}", query.sql());
 
-        query.execute(conn).await
+        let result = query.execute(conn).await?;
+        self.check_affected(result.rows_affected())?;
+        Ok(result)
+    }
+
+    async fn execute_returning<C, O>(&self, conn: &mut C) -> Result<Vec<O>, sqlx::Error>
+    where
+        for<'e> &'e mut C: sqlx::Executor<'e, Database = Self::DB>,
+        for<'r> O: FromRow<'r, <Self::DB as Database>::Row>,
+        O: Send + Unpin,
+    {
+        if self.skipped() {
+            // 没有需要更新的列
+            return Ok(vec![]);
+        }
+
+        let columns = self.set_columns();
+        let sql = self.generate_sql(&columns, true);
+        self.check(&sql)?;
+        let mut query = sqlx::query_as::<Self::DB, O>(&sql);
+
+        for col in &columns {
+            query = col.value.bind_to_query_as(query);
+        }
+
+        for expr in &self.exprs {
+            for param in &expr.params {
+                query = param.bind_to_query_as(query);
+            }
+        }
+
+        if let Some(w) = &self.wh {
+            query = w.bind_to_query_as(query);
+        }
+
+        tracing::debug!("easy-sqlx: {}", query.sql());
+
+        let result = query.fetch_all(conn).await?;
+        self.check_affected(result.len() as u64)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::{
+        builder::test_schema::{column, cond, pair, pk, table},
+        schema::column::Column,
+        dialects::condition::Operator,
+    };
+
+    #[test]
+    fn set_expr_array_param_shifts_following_placeholders() {
+        let builder = UpdateBuilder::new(table(vec![pk("id"), column("tags")]))
+            .set_expr(SetExpr::new(
+                "tags",
+                "array[$1, $2, $3]",
+                vec![Value::from(vec![1i64, 2, 3])],
+            ))
+            .set_expr(SetExpr::incr("id", 1i64))
+            .and(cond("id", Operator::Eq, 1i64));
+        let (sql, params) = builder.to_sql();
+        assert_eq!(
+            sql,
+            r#"update "public"."user" set "tags"=array[$1, $2, $3],"id"=coalesce("id", 0) + $4  where "id" = $5"#
+        );
+        assert_eq!(params.len(), 5);
+        assert_eq!(params[4], Value::from(1i64));
+    }
+
+    fn versioned() -> TableSchema {
+        table(vec![
+            pk("id"),
+            column("tags"),
+            Column {
+                version: true,
+                ..column("version")
+            },
+            Column {
+                column: Some("updated_at".to_string()),
+                updated_at: true,
+                ..column("updated")
+            },
+        ])
+    }
+
+    #[test]
+    fn check_version_numbers_sets_before_conditions() {
+        let builder = UpdateBuilder::new(versioned())
+            .set(pair("tags", "a".to_string()))
+            .set_expr(SetExpr::incr("version", 1))
+            .and(cond("id", Operator::Eq, 1i64))
+            .and(cond("version", Operator::Eq, 3i64))
+            .check_version();
+        let (sql, params) = builder.to_sql();
+        assert_eq!(
+            sql,
+            r#"update "public"."user" set "tags"=$1,"updated_at"=$2,"version"=coalesce("version", 0) + $3  where "id" = $4 and "version" = $5"#
+        );
+        assert_eq!(params[2], Value::from(1));
+        assert_eq!(params[3], Value::from(1i64));
+        assert_eq!(params[4], Value::from(3i64));
+    }
+
+    #[test]
+    fn check_version_rejects_unaffected_update() {
+        let builder = UpdateBuilder::new(versioned()).check_version();
+        let err = builder.check_affected(0).unwrap_err();
+        assert_eq!(
+            BuilderError::from_sqlx(&err),
+            Some(&BuilderError::StaleVersion("user".to_string()))
+        );
+        assert!(builder.check_affected(1).is_ok());
+        assert!(UpdateBuilder::new(versioned()).check_affected(0).is_ok());
+    }
+
+    #[test]
+    fn updated_at_is_not_overridden_when_set() {
+        let builder = UpdateBuilder::new(versioned())
+            .set_expr(SetExpr::new("updated_at", "now()", vec![]))
+            .and(cond("id", Operator::Eq, 1i64));
+        let (sql, params) = builder.to_sql();
+        assert_eq!(
+            sql,
+            r#"update "public"."user" set "updated_at"=now()  where "id" = $1"#
+        );
+        assert_eq!(params, vec![Value::from(1i64)]);
+    }
+
+    #[test]
+    fn update_without_condition_requires_all_rows() {
+        let user = table(vec![pk("id"), column("tags")]);
+        let builder = UpdateBuilder::new(user.clone()).set(pair("tags", "a".to_string()));
+        let err = builder.prepare().unwrap_err();
+        assert!(matches!(
+            BuilderError::from_sqlx(&err),
+            Some(BuilderError::Unconditioned(_))
+        ));
+
+        let builder = UpdateBuilder::new(user)
+            .set(pair("tags", "a".to_string()))
+            .all_rows();
+        let stmt = builder.prepare().unwrap();
+        assert!(!stmt.sql().contains("where"));
+    }
+
+    #[test]
+    fn update_without_sets_is_rejected() {
+        let builder =
+            UpdateBuilder::new(table(vec![pk("id")])).and(cond("id", Operator::Eq, 1i64));
+        assert!(matches!(builder.prepare(), Err(sqlx::Error::Configuration(_))));
+        let (sql, _) = builder.to_sql();
+        assert!(matches!(builder.check(&sql), Err(sqlx::Error::Configuration(_))));
+
+        // skip_empty 时执行直接返回，prepare 仍然没有可生成的语句
+        let builder = builder.skip_empty();
+        assert!(builder.skipped());
+        assert!(matches!(builder.prepare(), Err(sqlx::Error::Configuration(_))));
     }
 }
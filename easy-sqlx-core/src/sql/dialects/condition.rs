@@ -1,4 +1,9 @@
-use crate::sql::utils::{pair::Pair, quote::Quoter};
+use crate::sql::utils::{
+    pair::Pair,
+    placeholder::{max_placeholder, shift_placeholders},
+    quote::Quoter,
+    value::Value,
+};
 use chrono::NaiveDateTime;
 use sqlx::{Database, FromRow};
 
@@ -11,6 +16,7 @@ pub enum Operator {
     Lt,        // <
     Le,        // <=
     In,        // in
+    NotIn,     // not in
     Like,      // like
     IsNull,    // is null
     IsNotNull, // is not null
@@ -26,6 +32,7 @@ impl ToString for Operator {
             Operator::Lt => "lt".to_string(),
             Operator::Le => "le".to_string(),
             Operator::In => "in".to_string(),
+            Operator::NotIn => "not_in".to_string(),
             Operator::Like => "like".to_string(),
             Operator::IsNull => "is_null".to_string(),
             Operator::IsNotNull => "is_not_null".to_string(),
@@ -43,6 +50,7 @@ impl Operator {
             "lt" => Self::Lt,
             "le" => Self::Le,
             "in" => Self::In,
+            "not_in" => Self::NotIn,
             "like" => Self::Like,
             "is_null" => Self::IsNull,
             "is_not_null" => Self::IsNotNull,
@@ -79,6 +87,7 @@ impl Operator {
             Operator::Lt => "<".to_string(),
             Operator::Le => "<=".to_string(),
             Operator::In => "in".to_string(),
+            Operator::NotIn => "not in".to_string(),
             Operator::Like => "like".to_string(),
             Operator::IsNull => "is null".to_string(),
             Operator::IsNotNull => "is not null".to_string(),
@@ -88,34 +97,78 @@ impl Operator {
 
 #[derive(Debug, Clone)]
 pub enum Condition {
+    /// 列与参数比较: "name" = $1
     Condition(Pair, Operator),
+    /// 列与列比较: "updated_at" > "created_at"
+    Columns(String, Operator, String),
+    /// 列与 sql 表达式比较，表达式不绑定参数: "created_at" > now() - interval '1 day'
+    ColumnExpr(String, Operator, String),
+    /// sql 表达式与参数比较: lower("name") = $1
+    Expr(String, Operator, Value),
+    /// 原始 sql 片段及其参数，片段中的占位符从 $1 开始编号，生成 sql 时自动顺延
+    Raw(String, Vec<Value>),
     And(Box<Condition>, Box<Condition>),
     Or(Box<Condition>, Box<Condition>),
 }
 
 impl Condition {
+    /// 列与列比较，in / not in 生成 "a" in ("b")
+    /// ```rust,ignore
+    /// // "updated_at" > "created_at"
+    /// Condition::columns(User::col_updated_at(), Operator::Gt, User::col_created_at())
+    /// ```
+    pub fn columns(left: impl Into<String>, op: Operator, right: impl Into<String>) -> Self {
+        Condition::Columns(left.into(), op, right.into())
+    }
+
+    /// 列与 sql 表达式比较，表达式原样输出，不能包含参数
+    /// in / not in 时表达式放在括号中，可以是列表或子查询: "id" in (select ...)
+    /// ```rust,ignore
+    /// // "created_at" > now() - interval '1 day'
+    /// Condition::column_expr("created_at", Operator::Gt, "now() - interval '1 day'")
+    /// ```
+    pub fn column_expr(column: impl Into<String>, op: Operator, expr: impl Into<String>) -> Self {
+        Condition::ColumnExpr(column.into(), op, expr.into())
+    }
+
+    /// sql 表达式与参数比较，表达式原样输出
+    /// ```rust,ignore
+    /// // lower(name) = $1
+    /// Condition::expr("lower(name)", Operator::Eq, "abc".to_string())
+    /// ```
+    pub fn expr(expr: impl Into<String>, op: Operator, value: impl Into<Value>) -> Self {
+        Condition::Expr(expr.into(), op, value.into())
+    }
+
+    /// 原始 sql 片段，参数占位符从 $1 开始编号，与其它条件组合时自动顺延
+    /// 数组参数按元素展开绑定，每个元素占用一个占位符
+    /// ```rust,ignore
+    /// Condition::raw("age between $1 and $2", vec![Value::from(18), Value::from(30)])
+    /// ```
+    ///
+    /// # Panics
+    /// 最大的占位符编号与展开后的参数个数不一致时 panic
+    pub fn raw(sql: impl Into<String>, params: Vec<Value>) -> Self {
+        let sql = sql.into();
+        let binds: usize = params.iter().map(|v| v.bind_count()).sum();
+        let placeholders = max_placeholder(&sql);
+        assert!(
+            placeholders == binds,
+            "raw condition `{sql}` uses {placeholders} placeholders but binds {binds} values"
+        );
+        Condition::Raw(sql, params)
+    }
+
     pub fn is_condition(&self) -> bool {
-        match self {
-            Condition::Condition(_, _) => true,
-            Condition::And(_, _) => false,
-            Condition::Or(_, _) => false,
-        }
+        !self.is_and() && !self.is_or()
     }
 
     pub fn is_and(&self) -> bool {
-        match self {
-            Condition::Condition(_, _) => false,
-            Condition::And(_, _) => true,
-            Condition::Or(_, _) => false,
-        }
+        matches!(self, Condition::And(_, _))
     }
 
     pub fn is_or(&self) -> bool {
-        match self {
-            Condition::Condition(_, _) => false,
-            Condition::And(_, _) => false,
-            Condition::Or(_, _) => true,
-        }
+        matches!(self, Condition::Or(_, _))
     }
 }
 
@@ -152,6 +205,21 @@ impl Condition {
                     p.value.bind_to_query(query)
                 }
             }
+            Condition::Expr(_, o, v) => {
+                if o.is_no_param() {
+                    query
+                } else {
+                    v.bind_to_query(query)
+                }
+            }
+            Condition::Raw(_, params) => {
+                let mut qry = query;
+                for v in params {
+                    qry = v.bind_to_query(qry);
+                }
+                qry
+            }
+            Condition::Columns(_, _, _) | Condition::ColumnExpr(_, _, _) => query,
             Condition::And(left, right) => {
                 let qry = left.bind_to_query(query);
                 right.bind_to_query(qry)
@@ -195,6 +263,21 @@ impl Condition {
                     p.value.bind_to_query_as(query)
                 }
             }
+            Condition::Expr(_, o, v) => {
+                if o.is_no_param() {
+                    query
+                } else {
+                    v.bind_to_query_as(query)
+                }
+            }
+            Condition::Raw(_, params) => {
+                let mut qry = query;
+                for v in params {
+                    qry = v.bind_to_query_as(qry);
+                }
+                qry
+            }
+            Condition::Columns(_, _, _) | Condition::ColumnExpr(_, _, _) => query,
             Condition::And(left, right) => {
                 let qry = left.bind_to_query_as(query);
                 right.bind_to_query_as(qry)
@@ -238,6 +321,21 @@ impl Condition {
                     p.value.bind_to_query_scalar(query)
                 }
             }
+            Condition::Expr(_, o, v) => {
+                if o.is_no_param() {
+                    query
+                } else {
+                    v.bind_to_query_scalar(query)
+                }
+            }
+            Condition::Raw(_, params) => {
+                let mut qry = query;
+                for v in params {
+                    qry = v.bind_to_query_scalar(qry);
+                }
+                qry
+            }
+            Condition::Columns(_, _, _) | Condition::ColumnExpr(_, _, _) => query,
             Condition::And(left, right) => {
                 let qry = left.bind_to_query_scalar(query);
                 right.bind_to_query_scalar(qry)
@@ -249,35 +347,94 @@ impl Condition {
         }
     }
 
-    pub fn sql(&self, param_index: usize, quoter: &Quoter) -> (String, usize) {
+    /// 按绑定顺序追加条件的参数，与 bind_to_query 一致
+    pub fn append_params(&self, params: &mut Vec<Value>) {
         match self {
             Condition::Condition(p, o) => {
-                let field = p.name.clone();
-                let op = o.sql();
-
-                if o.is_no_param() {
-                    // 不需要参数
-                    return (format!("{} {op}", quoter.quote(&field)), param_index);
+                if !o.is_no_param() {
+                    p.value.append_params(params);
+                }
+            }
+            Condition::Expr(_, o, v) => {
+                if !o.is_no_param() {
+                    v.append_params(params);
                 }
+            }
+            Condition::Raw(_, values) => {
+                for v in values {
+                    v.append_params(params);
+                }
+            }
+            Condition::Columns(_, _, _) | Condition::ColumnExpr(_, _, _) => {}
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                left.append_params(params);
+                right.append_params(params);
+            }
+        }
+    }
 
-                if *o == Operator::In {
-                    // in 操作
-                    // vec![0; p.value.get_len()].iter().map(|n| );
-                    let mut params = vec![];
-                    for n in 0..p.value.len() {
-                        params.push(format!("${}", param_index + n));
-                    }
+    /// 生成 left op 参数 形式的条件
+    fn sql_operand(left: String, o: &Operator, value: &Value, param_index: usize) -> (String, usize) {
+        let op = o.sql();
+
+        if o.is_no_param() {
+            // 不需要参数
+            return (format!("{left} {op}"), param_index);
+        }
+
+        if *o == Operator::In || *o == Operator::NotIn {
+            // in 操作
+            if value.is_empty() {
+                // 空列表: in () 恒为假，not in () 恒为真，不绑定参数
+                let constant = if *o == Operator::In { "1 = 0" } else { "1 = 1" };
+                return (constant.to_string(), param_index);
+            }
+            let mut params = vec![];
+            for n in 0..value.len() {
+                params.push(format!("${}", param_index + n));
+            }
+
+            return (
+                format!("{left} {op} ({})", params.join(",")),
+                param_index + value.len(),
+            );
+        }
+        (format!("{left} {op} ${param_index}"), param_index + 1)
+    }
 
-                    return (
-                        format!("{} {op} ({})", quoter.quote(&field), params.join(",")),
-                        param_index + p.value.len(),
-                    );
+    pub fn sql(&self, param_index: usize, quoter: &Quoter) -> (String, usize) {
+        match self {
+            Condition::Condition(p, o) => {
+                Self::sql_operand(quoter.quote(&p.name), o, &p.value, param_index)
+            }
+            Condition::Columns(left, o, right) => {
+                let left = quoter.quote(left);
+                if o.is_no_param() {
+                    return (format!("{left} {}", o.sql()), param_index);
                 }
-                (
-                    format!("{} {op} ${param_index}", quoter.quote(&field)),
-                    param_index + 1,
-                )
+                let right = quoter.quote(right);
+                if *o == Operator::In || *o == Operator::NotIn {
+                    return (format!("{left} {} ({right})", o.sql()), param_index);
+                }
+                (format!("{left} {} {right}", o.sql()), param_index)
             }
+            Condition::ColumnExpr(column, o, expr) => {
+                let column = quoter.quote(column);
+                if o.is_no_param() {
+                    return (format!("{column} {}", o.sql()), param_index);
+                }
+                if *o == Operator::In || *o == Operator::NotIn {
+                    return (format!("{column} {} ({expr})", o.sql()), param_index);
+                }
+                (format!("{column} {} {expr}", o.sql()), param_index)
+            }
+            Condition::Expr(expr, o, value) => {
+                Self::sql_operand(expr.clone(), o, value, param_index)
+            }
+            Condition::Raw(sql, params) => (
+                format!("({})", shift_placeholders(sql, param_index)),
+                param_index + params.iter().map(|v| v.bind_count()).sum::<usize>(),
+            ),
             Condition::And(left, right) => {
                 let (left_cond, index) = left.sql(param_index, quoter);
                 let (right_cond, index) = right.sql(index, quoter);
@@ -386,6 +543,20 @@ impl Where {
         }
     }
 
+    /// 是否没有任何条件
+    pub fn is_empty(&self) -> bool {
+        self.cond.is_none()
+    }
+
+    /// 条件绑定的参数，顺序与生成的 sql 中的占位符一致
+    pub fn params(&self) -> Vec<Value> {
+        let mut params = vec![];
+        if let Some(cond) = &self.cond {
+            cond.append_params(&mut params);
+        }
+        params
+    }
+
     pub fn bind_to_query<'a, DB: Database>(
         &self,
         query: sqlx::query::Query<'a, DB, DB::Arguments<'a>>,
@@ -489,3 +660,81 @@ impl Where {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::utils::quote::always_reserve;
+
+    fn quoter() -> Quoter {
+        Quoter::new(b'"', b'"', always_reserve)
+    }
+
+    #[test]
+    fn raw_array_param_shifts_following_placeholders() {
+        let wh = Where::new(Condition::raw(
+            "id in ($1, $2, $3)",
+            vec![Value::from(vec![1i64, 2, 3])],
+        ))
+        .and(Condition::Condition(
+            Pair {
+                name: "name".to_string(),
+                value: Value::from("abc".to_string()),
+            },
+            Operator::Eq,
+        ));
+        let (sql, index) = wh.sql(1, &quoter());
+        assert_eq!(sql, r#"(id in ($1, $2, $3)) and "name" = $4"#);
+        assert_eq!(index, 5);
+        assert_eq!(wh.params().len(), 4);
+    }
+
+    #[test]
+    fn raw_placeholders_follow_previous_conditions() {
+        let wh = Where::new(Condition::Condition(
+            Pair {
+                name: "id".to_string(),
+                value: Value::from(vec![1i64, 2]),
+            },
+            Operator::In,
+        ))
+        .and(Condition::raw("age between $1 and $2", vec![Value::from(18), Value::from(30)]));
+        let (sql, index) = wh.sql(1, &quoter());
+        assert_eq!(sql, r#""id" in ($1,$2) and (age between $3 and $4)"#);
+        assert_eq!(index, 5);
+    }
+
+    #[test]
+    fn column_in_renders_parenthesized_right_side() {
+        let cond = Condition::columns("id", Operator::In, "owner_id");
+        assert_eq!(cond.sql(1, &quoter()), (r#""id" in ("owner_id")"#.to_string(), 1));
+
+        let cond = Condition::column_expr("id", Operator::NotIn, r#"select "user_id" from "ban""#);
+        assert_eq!(
+            cond.sql(1, &quoter()).0,
+            r#""id" not in (select "user_id" from "ban")"#
+        );
+
+        let cond = Condition::column_expr("created_at", Operator::Gt, "now()");
+        assert_eq!(cond.sql(1, &quoter()).0, r#""created_at" > now()"#);
+    }
+
+    #[test]
+    fn raw_placeholder_count_matches_expanded_binds() {
+        Condition::raw("id in ($1, $2) and name = $3", vec![Value::from(vec![1i64, 2]), Value::from("a".to_string())]);
+        // 引号中的 $ 不是占位符
+        Condition::raw("name = '$1'", vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "uses 2 placeholders but binds 1 values")]
+    fn raw_with_missing_param_panics() {
+        Condition::raw("age between $1 and $2", vec![Value::from(18)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "uses 1 placeholders but binds 3 values")]
+    fn raw_with_unexpanded_array_placeholder_panics() {
+        Condition::raw("id = any($1)", vec![Value::from(vec![1i64, 2, 3])]);
+    }
+}
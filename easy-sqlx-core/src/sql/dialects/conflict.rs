@@ -0,0 +1,61 @@
+use crate::sql::{schema::table::TableSchema, utils::pair::Pair};
+
+/// 冲突目标
+#[derive(Clone, Debug)]
+pub enum ConflictTarget {
+    /// 唯一约束（主键或唯一索引）的列
+    Columns(Vec<String>),
+    /// 唯一索引名称，可以是定义时的名称或生成的完整索引名称
+    Index(String),
+}
+
+/// 发生冲突时的处理方式
+#[derive(Clone, Debug)]
+pub enum ConflictAction {
+    /// do nothing
+    Nothing,
+    /// do update set 列 = 值
+    Update(Vec<Pair>),
+    /// do update set 列 = excluded.列
+    UpdateExcluded(Vec<String>),
+}
+
+/// insert ... on conflict (...) do ...
+#[derive(Clone, Debug)]
+pub struct OnConflict {
+    pub target: ConflictTarget,
+    pub action: ConflictAction,
+}
+
+impl OnConflict {
+    pub fn new(target: ConflictTarget) -> Self {
+        Self {
+            target,
+            action: ConflictAction::Nothing,
+        }
+    }
+
+    /// 获取冲突目标的列，索引不存在时返回 None
+    pub fn target_columns(&self, table: &TableSchema) -> Option<Vec<String>> {
+        match &self.target {
+            ConflictTarget::Columns(cols) => Some(cols.clone()),
+            ConflictTarget::Index(name) => table
+                .indexes
+                .as_ref()?
+                .iter()
+                .find(|idx| {
+                    idx.name.to_uppercase() == name.to_uppercase()
+                        || idx.get_name(&table.name).0.to_uppercase() == name.to_uppercase()
+                })
+                .map(|idx| idx.columns.clone()),
+        }
+    }
+
+    /// do update set 列 = 值 时需要绑定的参数
+    pub fn pairs(&self) -> &[Pair] {
+        match &self.action {
+            ConflictAction::Update(pairs) => pairs,
+            _ => &[],
+        }
+    }
+}
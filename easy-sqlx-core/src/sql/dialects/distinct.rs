@@ -0,0 +1,53 @@
+use super::page::Order;
+
+/// select 去重方式
+#[derive(Clone, Debug, PartialEq)]
+pub enum Distinct {
+    /// select distinct，对整行去重
+    All,
+    /// select distinct on (...)，每组只保留排序后的第一行
+    On(Vec<String>),
+}
+
+impl Distinct {
+    /// 生成 distinct 子句，字段使用 quote 函数处理
+    pub fn sql<F>(&self, quote: F) -> String
+    where
+        F: Fn(&String) -> String,
+    {
+        match self {
+            Distinct::All => "distinct".to_string(),
+            Distinct::On(cols) => {
+                let items: Vec<String> = cols.iter().map(quote).collect();
+                format!("distinct on ({})", items.join(", "))
+            }
+        }
+    }
+
+    /// distinct on 要求 order by 的开头与 distinct on 的字段一致
+    /// 将 distinct on 的字段放到排序的最前面，已设置排序的字段保留排序方向，其余排序依次排在后面
+    /// 未设置排序时不做处理
+    pub fn orders(&self, orders: &[Order]) -> Vec<Order> {
+        let cols = match self {
+            Distinct::On(cols) if !orders.is_empty() => cols,
+            _ => return orders.to_vec(),
+        };
+        let mut result: Vec<Order> = cols
+            .iter()
+            .map(|c| {
+                orders
+                    .iter()
+                    .find(|o| !o.expr && &o.field == c)
+                    .cloned()
+                    .unwrap_or_else(|| Order::new(c.clone()))
+            })
+            .collect();
+        result.extend(
+            orders
+                .iter()
+                .filter(|o| o.expr || !cols.contains(&o.field))
+                .cloned(),
+        );
+        result
+    }
+}
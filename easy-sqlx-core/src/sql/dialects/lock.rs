@@ -0,0 +1,69 @@
+/// 行锁强度
+#[derive(Clone, Debug, PartialEq)]
+pub enum LockStrength {
+    /// for update
+    Update,
+    /// for no key update
+    NoKeyUpdate,
+    /// for share
+    Share,
+    /// for key share
+    KeyShare,
+}
+
+impl LockStrength {
+    pub fn sql(&self) -> String {
+        match self {
+            LockStrength::Update => "for update".to_string(),
+            LockStrength::NoKeyUpdate => "for no key update".to_string(),
+            LockStrength::Share => "for share".to_string(),
+            LockStrength::KeyShare => "for key share".to_string(),
+        }
+    }
+}
+
+/// 行已被其它事务锁定时的处理方式
+#[derive(Clone, Debug, PartialEq)]
+pub enum LockWait {
+    /// 等待锁释放
+    Wait,
+    /// nowait 立即报错
+    NoWait,
+    /// skip locked 跳过已锁定的行
+    SkipLocked,
+}
+
+impl LockWait {
+    pub fn sql(&self) -> String {
+        match self {
+            LockWait::Wait => "".to_string(),
+            LockWait::NoWait => "nowait".to_string(),
+            LockWait::SkipLocked => "skip locked".to_string(),
+        }
+    }
+}
+
+/// select 语句的行锁，需要在事务中使用
+#[derive(Clone, Debug)]
+pub struct RowLock {
+    pub strength: LockStrength,
+    pub wait: LockWait,
+}
+
+impl RowLock {
+    pub fn new(strength: LockStrength) -> Self {
+        Self {
+            strength,
+            wait: LockWait::Wait,
+        }
+    }
+
+    pub fn sql(&self) -> String {
+        let wait = self.wait.sql();
+        if wait.is_empty() {
+            self.strength.sql()
+        } else {
+            format!("{} {wait}", self.strength.sql())
+        }
+    }
+}
@@ -1,4 +1,7 @@
 pub mod condition;
 pub mod context;
 pub mod schema;
-pub mod page;
\ No newline at end of file
+pub mod page;
+pub mod lock;
+pub mod distinct;
+pub mod conflict;
\ No newline at end of file
@@ -1,3 +1,7 @@
+use chrono::NaiveDateTime;
+
+use crate::sql::utils::value::Value;
+
 #[derive(Clone, Debug)]
 pub enum OrderType {
     Asc,
@@ -15,34 +19,107 @@ impl OrderType {
     }
 }
 
+/// 空值排序位置
+#[derive(Clone, Debug, PartialEq)]
+pub enum NullsOrder {
+    /// nulls first
+    First,
+    /// nulls last
+    Last,
+}
+
+impl NullsOrder {
+    pub fn sql(&self) -> String {
+        match self {
+            NullsOrder::First => "nulls first".to_string(),
+            NullsOrder::Last => "nulls last".to_string(),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Order {
+    /// 数据库列名称，expr 为 true 时为 sql 表达式
     pub field: String,
     pub order_type: OrderType,
+    /// 空值排在最前或最后，None 时使用数据库默认规则（升序时空值在后）
+    pub nulls: Option<NullsOrder>,
+    /// field 是否为 sql 表达式，表达式原样写入 order by，不加引号
+    pub expr: bool,
 }
 
 impl Order {
     /// 字段升序
     pub fn asc(field: String) -> Self {
-        Self {
-            field,
-            order_type: OrderType::Asc,
-        }
+        Self::with_type(field, OrderType::Asc)
     }
     /// 字段降序
     pub fn desc(field: String) -> Self {
-        Self {
-            field,
-            order_type: OrderType::Desc,
-        }
+        Self::with_type(field, OrderType::Desc)
     }
     /// 字段默认顺序
     pub fn new(field: String) -> Self {
+        Self::with_type(field, OrderType::None)
+    }
+
+    fn with_type(field: String, order_type: OrderType) -> Self {
         Self {
             field,
-            order_type: OrderType::None,
+            order_type,
+            nulls: None,
+            expr: false,
+        }
+    }
+
+    /// 按 sql 表达式排序，如 Order::expr("length(\"name\")", OrderType::Desc)
+    /// 表达式不做任何处理，不要拼接外部输入
+    pub fn expr<S: Into<String>>(expr: S, order_type: OrderType) -> Self {
+        Self {
+            field: expr.into(),
+            order_type,
+            nulls: None,
+            expr: true,
         }
     }
+
+    /// 随机顺序 order by random()
+    pub fn random() -> Self {
+        Self::expr("random()", OrderType::None)
+    }
+
+    /// 空值排在最前
+    pub fn nulls_first(mut self) -> Self {
+        self.nulls = Some(NullsOrder::First);
+        self
+    }
+
+    /// 空值排在最后
+    pub fn nulls_last(mut self) -> Self {
+        self.nulls = Some(NullsOrder::Last);
+        self
+    }
+
+    /// 生成 order by 中的一项，列名称使用 quote 函数处理，表达式原样输出
+    pub fn sql<F>(&self, quote: F) -> String
+    where
+        F: Fn(&String) -> String,
+    {
+        let mut sql = if self.expr {
+            self.field.clone()
+        } else {
+            quote(&self.field)
+        };
+        let order_type = self.order_type.sql();
+        if !order_type.is_empty() {
+            sql.push(' ');
+            sql.push_str(&order_type);
+        }
+        if let Some(nulls) = &self.nulls {
+            sql.push(' ');
+            sql.push_str(&nulls.sql());
+        }
+        sql
+    }
 }
 
 pub struct PageRequest {
@@ -126,3 +203,214 @@ where
         }
     }
 }
+
+/// 游标（keyset）分页请求
+/// 按照查询的排序列定位下一页的起始位置，不使用 offset，深分页时性能稳定
+pub struct CursorRequest {
+    /// 每页记录数
+    size: usize,
+    /// 上一页返回的游标，为 None 时从第一条记录开始
+    cursor: Option<String>,
+}
+
+impl CursorRequest {
+    pub fn new(size: usize, cursor: Option<String>) -> Self {
+        Self { size, cursor }
+    }
+
+    pub fn get_size(&self) -> usize {
+        if self.size == 0 {
+            20
+        } else {
+            self.size
+        }
+    }
+
+    pub fn get_cursor(&self) -> Option<&String> {
+        self.cursor.as_ref()
+    }
+}
+
+#[derive(Default)]
+pub struct CursorResult<O>
+where
+    O: std::marker::Send,
+    O: Unpin,
+{
+    /// 每页记录数
+    pub size: usize,
+    /// 下一页的游标，没有更多记录时为 None
+    pub next_cursor: Option<String>,
+    /// 记录
+    pub records: Vec<O>,
+}
+
+const CURSOR_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// 将游标位置的值编码为不透明的字符串
+/// 每个值编码为 类型标记 + 长度 + ':' + 内容，空值的长度为 '-'，最后整体转为十六进制
+pub fn encode_cursor(values: &[Value]) -> String {
+    let mut buf = String::new();
+    for value in values {
+        let (tag, content) = match value {
+            Value::Bool(v) => ('b', v.map(|v| v.to_string())),
+            Value::Short(v) => ('s', v.map(|v| v.to_string())),
+            Value::Int(v) => ('i', v.map(|v| v.to_string())),
+            Value::Long(v) => ('l', v.map(|v| v.to_string())),
+            Value::Float(v) => ('f', v.map(|v| v.to_string())),
+            Value::Double(v) => ('d', v.map(|v| v.to_string())),
+            Value::Text(v) => ('t', v.clone()),
+            Value::ChronoDate(v) => ('c', v.map(|v| v.format(CURSOR_TIME_FORMAT).to_string())),
+            Value::Binary(v) => ('x', v.as_ref().map(|v| to_hex(v))),
+            Value::Array(_) => continue,
+        };
+        buf.push(tag);
+        if let Some(content) = content {
+            buf.push_str(format!("{}:{content}", content.len()).as_str());
+        } else {
+            buf.push_str("-:");
+        }
+    }
+    to_hex(buf.as_bytes())
+}
+
+/// 解析 encode_cursor 生成的游标，游标格式不正确时返回 None
+pub fn decode_cursor(cursor: &str) -> Option<Vec<Value>> {
+    let raw = String::from_utf8(from_hex(cursor)?).ok()?;
+    let mut values = vec![];
+    let mut rest = raw.as_str();
+    while !rest.is_empty() {
+        let tag = rest.chars().next()?;
+        // 游标来自客户端，标记可能是多字节字符
+        let (len, tail) = rest[tag.len_utf8()..].split_once(':')?;
+        let content = if len == "-" {
+            rest = tail;
+            None
+        } else {
+            let len: usize = len.parse().ok()?;
+            let content = tail.get(..len)?;
+            rest = &tail[len..];
+            Some(content)
+        };
+
+        let value = match tag {
+            'b' => Value::Bool(content.map(|c| c.parse()).transpose().ok()?),
+            's' => Value::Short(content.map(|c| c.parse()).transpose().ok()?),
+            'i' => Value::Int(content.map(|c| c.parse()).transpose().ok()?),
+            'l' => Value::Long(content.map(|c| c.parse()).transpose().ok()?),
+            'f' => Value::Float(content.map(|c| c.parse()).transpose().ok()?),
+            'd' => Value::Double(content.map(|c| c.parse()).transpose().ok()?),
+            't' => Value::Text(content.map(|c| c.to_string())),
+            'c' => Value::ChronoDate(
+                content
+                    .map(|c| NaiveDateTime::parse_from_str(c, CURSOR_TIME_FORMAT))
+                    .transpose()
+                    .ok()?,
+            ),
+            'x' => Value::Binary(match content {
+                Some(c) => Some(from_hex(c)?),
+                None => None,
+            }),
+            _ => return None,
+        };
+        values.push(value);
+    }
+    Some(values)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(str: &str) -> Option<Vec<u8>> {
+    if str.len() % 2 != 0 {
+        return None;
+    }
+    (0..str.len())
+        .step_by(2)
+        .map(|n| u8::from_str_radix(str.get(n..n + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(name: &String) -> String {
+        format!("\"{name}\"")
+    }
+
+    #[test]
+    fn column_order_is_quoted() {
+        assert_eq!(Order::asc("name".to_string()).sql(quote), r#""name" asc"#);
+        assert_eq!(Order::new("name".to_string()).sql(quote), r#""name""#);
+    }
+
+    #[test]
+    fn expr_order_is_written_as_is() {
+        let order = Order::expr(r#"length("name")"#, OrderType::Desc);
+        assert_eq!(order.sql(quote), r#"length("name") desc"#);
+        assert_eq!(Order::random().sql(quote), "random()");
+    }
+
+    #[test]
+    fn nulls_order_follows_direction() {
+        let order = Order::desc("create_time".to_string()).nulls_last();
+        assert_eq!(order.sql(quote), r#""create_time" desc nulls last"#);
+        let order = Order::new("create_time".to_string()).nulls_first();
+        assert_eq!(order.sql(quote), r#""create_time" nulls first"#);
+        let order = Order::expr("lower(name)", OrderType::Asc).nulls_first();
+        assert_eq!(order.sql(quote), "lower(name) asc nulls first");
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let time = NaiveDateTime::parse_from_str("2024-05-06T07:08:09.123456", CURSOR_TIME_FORMAT)
+            .unwrap();
+        let values = vec![
+            Value::Bool(Some(true)),
+            Value::Short(Some(-3)),
+            Value::Int(Some(42)),
+            Value::Long(Some(i64::MAX)),
+            Value::Float(Some(1.5)),
+            Value::Double(Some(-2.25)),
+            Value::Text(Some("a:b 中文".to_string())),
+            Value::Text(Some("".to_string())),
+            Value::ChronoDate(Some(time)),
+            Value::Binary(Some(vec![0, 1, 255])),
+            Value::Long(None),
+            Value::Text(None),
+            Value::Binary(None),
+        ];
+        let cursor = encode_cursor(&values);
+        assert_eq!(decode_cursor(&cursor), Some(values));
+    }
+
+    #[test]
+    fn decode_empty_cursor() {
+        assert_eq!(decode_cursor(""), Some(vec![]));
+    }
+
+    #[test]
+    fn decode_malformed_cursor() {
+        // 不是十六进制
+        assert_eq!(decode_cursor("zz"), None);
+        // 奇数长度
+        assert_eq!(decode_cursor("6"), None);
+        // 不是 utf-8
+        assert_eq!(decode_cursor("ff"), None);
+        // 多字节的类型标记
+        assert_eq!(decode_cursor(&to_hex("é1:x".as_bytes())), None);
+        // 内容长度超出
+        assert_eq!(decode_cursor(&to_hex(b"l5:12")), None);
+        // 缺少长度分隔符
+        assert_eq!(decode_cursor(&to_hex(b"l12")), None);
+        // 长度不是数字
+        assert_eq!(decode_cursor(&to_hex(b"la:1")), None);
+        // 未知的类型标记
+        assert_eq!(decode_cursor(&to_hex(b"q1:1")), None);
+        // 内容与类型不符
+        assert_eq!(decode_cursor(&to_hex(b"i3:abc")), None);
+        assert_eq!(decode_cursor(&to_hex(b"x1:z")), None);
+    }
+}
@@ -7,11 +7,15 @@ use sqlx::{ColumnIndex, Database, Decode, Encode, Executor, IntoArguments};
 use crate::sql::{
     dialects::{
         condition::Where,
+        conflict::ConflictAction,
         context,
+        distinct::Distinct,
+        lock::RowLock,
         page::{Order, PageRequest},
         schema::schema::Schema,
     },
     schema::{column::Column, index::Index, table::TableSchema, types::convert_sql_type},
+    utils::{pair::SetExpr, placeholder::shift_placeholders},
 };
 
 use super::{
@@ -354,29 +358,112 @@ impl Schema for PgSchema
     }
 
     fn sql_insert_columns(&self, table: &TableSchema, cols: &Vec<String>) -> String {
+        self.sql_insert_rows(table, cols, &[vec![true; cols.len()]])
+    }
+
+    fn sql_insert_rows(&self, table: &TableSchema, cols: &[String], rows: &[Vec<bool>]) -> String {
+        if cols.is_empty() {
+            // 没有列时全部使用默认值，只能插入一行
+            return format!(
+                "insert into {} default values",
+                self.ctx.quote(&self.table_name_with_schema(table))
+            );
+        }
+
         // let table_name = table.name_with_schema();
         let mut column_names = "".to_string();
-        let mut column_value_holder = "".to_string();
+        let mut values = "".to_string();
 
         for (n, col) in cols.iter().enumerate() {
             if n > 0 {
-                column_names.push_str(",");
-                column_value_holder.push_str(",");
+                column_names.push(',');
             }
             column_names.push_str(self.ctx.quote(&col).as_str());
-            column_value_holder.push_str(format!("${}", n + 1).as_str());
+        }
+
+        let mut param_index = 1;
+        for (row, presents) in rows.iter().enumerate() {
+            if row > 0 {
+                values.push(',');
+            }
+            let mut column_value_holder = "".to_string();
+            for n in 0..cols.len() {
+                if n > 0 {
+                    column_value_holder.push(',');
+                }
+                if presents.get(n).copied().unwrap_or(false) {
+                    column_value_holder.push_str(format!("${param_index}").as_str());
+                    param_index += 1;
+                } else {
+                    column_value_holder.push_str("default");
+                }
+            }
+            values.push_str(format!("({column_value_holder})").as_str());
         }
 
         format!(
-            "insert into {} ({column_names}) values ({column_value_holder})",
+            "insert into {} ({column_names}) values {values}",
             self.ctx.quote(&self.table_name_with_schema(table))
         )
     }
 
+    fn sql_returning(&self, cols: &[String]) -> String {
+        if cols.is_empty() {
+            return "".to_string();
+        }
+        let items: Vec<String> = cols.iter().map(|c| self.quoter().quote(c)).collect();
+        format!(" returning {}", items.join(","))
+    }
+
+    fn sql_on_conflict(
+        &self,
+        target: &[String],
+        action: &ConflictAction,
+        param_index: usize,
+    ) -> String {
+        let mut target_str = String::from("");
+        if !target.is_empty() {
+            let cols: Vec<String> = target.iter().map(|c| self.ctx.quote(c)).collect();
+            target_str = format!(" ({})", cols.join(","));
+        }
+
+        let sets: Vec<String> = match action {
+            ConflictAction::Nothing => vec![],
+            ConflictAction::Update(pairs) => pairs
+                .iter()
+                .enumerate()
+                .map(|(n, p)| format!("{}=${}", self.ctx.quote(&p.name), param_index + n))
+                .collect(),
+            ConflictAction::UpdateExcluded(cols) => cols
+                .iter()
+                .map(|c| {
+                    let col = self.ctx.quote(c);
+                    format!("{col}=excluded.{col}")
+                })
+                .collect(),
+        };
+
+        if sets.is_empty() {
+            format!(" on conflict{target_str} do nothing")
+        } else {
+            format!(" on conflict{target_str} do update set {}", sets.join(","))
+        }
+    }
+
+    fn sql_copy_in(&self, table: &TableSchema, cols: &[String]) -> String {
+        let column_names: Vec<String> = cols.iter().map(|c| self.ctx.quote(c)).collect();
+        format!(
+            "copy {} ({}) from stdin",
+            self.ctx.quote(&self.table_name_with_schema(table)),
+            column_names.join(",")
+        )
+    }
+
     fn sql_update_columns(
         &self,
         table: &TableSchema,
         cols: &Vec<String>,
+        exprs: &[SetExpr],
         wh: Option<Where>,
     ) -> String {
         let mut columns = "".to_string();
@@ -390,9 +477,20 @@ impl Schema for PgSchema
             columns.push_str(format!("${}", n + 1).as_str());
         }
 
+        let mut param_index = cols.len() + 1;
+        for expr in exprs {
+            if !columns.is_empty() {
+                columns.push(',');
+            }
+            columns.push_str(self.ctx.quote(&expr.name).as_str());
+            columns.push('=');
+            columns.push_str(shift_placeholders(&expr.expr, param_index).as_str());
+            param_index += expr.params.iter().map(|v| v.bind_count()).sum::<usize>();
+        }
+
         let mut where_str = String::from("");
         if let Some(w) = wh {
-            let (ws, _) = w.sql(cols.len() + 1, &self.quoter());
+            let (ws, _) = w.sql(param_index, &self.quoter());
             if !ws.is_empty() {
                 where_str.push_str(" where ");
                 where_str.push_str(&ws);
@@ -425,7 +523,18 @@ impl Schema for PgSchema
         )
     }
 
-    fn sql_count(&self, table: &TableSchema, wh: Option<Where>) -> String {
+    fn sql_count(
+        &self,
+        table: &TableSchema,
+        wh: Option<Where>,
+        distinct: Option<&Distinct>,
+    ) -> String {
+        if distinct.is_some() {
+            // 去重后统计，使用子查询
+            let sql = self.sql_select(table, wh, &vec![], None, None, distinct);
+            return format!(r#"select count(0) as "count" from ({sql}) as "t""#);
+        }
+
         let mut where_str = String::from("");
         if let Some(w) = wh {
             let (ws, _) = w.sql(1, &self.quoter());
@@ -447,13 +556,15 @@ impl Schema for PgSchema
         wh: Option<Where>,
         orders: &Vec<Order>,
         pg: Option<&PageRequest>,
+        lock: Option<&RowLock>,
+        distinct: Option<&Distinct>,
     ) -> String {
         let cols: Vec<String> = table
             .columns
             .iter()
             .map(|c| c.get_query_column_name())
             .collect();
-        self.sql_select_columns(table, &cols, wh, orders, pg)
+        self.sql_select_columns(table, &cols, wh, orders, pg, lock, distinct)
         // let cols: Vec<String> = table
         //     .columns
         //     .iter()
@@ -506,6 +617,8 @@ impl Schema for PgSchema
         wh: Option<Where>,
         orders: &Vec<Order>,
         pg: Option<&PageRequest>,
+        lock: Option<&RowLock>,
+        distinct: Option<&Distinct>,
     ) -> String {
         let cols: Vec<String> = columns.iter().map(|c| self.quoter().quote(&c)).collect();
 
@@ -518,12 +631,20 @@ impl Schema for PgSchema
             }
         }
 
+        let mut distinct_str = String::from("");
+        let mut orders = orders.clone();
+        if let Some(distinct) = distinct {
+            distinct_str.push_str(&distinct.sql(|c| self.ctx.quote(c)));
+            distinct_str.push(' ');
+            orders = distinct.orders(&orders);
+        }
+
         let mut order_str = String::from("");
         if !orders.is_empty() {
             order_str.push_str(" order by ");
             let items: Vec<String> = orders
                 .iter()
-                .map(|o| format!("{} {}", self.ctx.quote(&o.field), o.order_type.sql()))
+                .map(|o| o.sql(|c| self.ctx.quote(c)))
                 .collect();
             order_str.push_str(items.join(", ").as_str());
         }
@@ -541,8 +662,15 @@ impl Schema for PgSchema
             );
         }
 
+        let mut lock_str = String::from("");
+        if let Some(lock) = lock {
+            // 行锁
+            lock_str.push(' ');
+            lock_str.push_str(&lock.sql());
+        }
+
         format!(
-            "select {} from {} {where_str} {order_str} {page_str}",
+            "select {distinct_str}{} from {} {where_str} {order_str} {page_str}{lock_str}",
             cols.join(","),
             self.ctx.quote(&self.table_name_with_schema(table))
         )
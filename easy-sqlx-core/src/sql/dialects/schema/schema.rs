@@ -3,10 +3,13 @@ use sqlx::{ColumnIndex, Database, Decode, Encode, Executor, IntoArguments};
 use crate::sql::{
     dialects::{
         condition::Where,
+        conflict::ConflictAction,
+        distinct::Distinct,
+        lock::RowLock,
         page::{Order, PageRequest},
     },
     schema::{column::Column, index::Index, table::TableSchema},
-    utils::quote::Quoter,
+    utils::{pair::SetExpr, quote::Quoter},
 };
 use std::{future::Future, io};
 
@@ -108,15 +111,36 @@ pub trait Schema
 
     fn sql_insert(&self, table: &TableSchema) -> String;
     fn sql_insert_columns(&self, table: &TableSchema, cols: &Vec<String>) -> String;
+    /// 多行插入 insert into ... values (...), (...)
+    /// rows 为每行各列是否有值，没有值的列使用 default
+    fn sql_insert_rows(&self, table: &TableSchema, cols: &[String], rows: &[Vec<bool>]) -> String;
+    /// returning 子句，没有列时返回空字符串
+    fn sql_returning(&self, cols: &[String]) -> String;
+    /// insert 语句的 on conflict 子句，param_index 为 do update set 第一个参数的序号
+    fn sql_on_conflict(
+        &self,
+        target: &[String],
+        action: &ConflictAction,
+        param_index: usize,
+    ) -> String;
+    /// copy ... from stdin，使用 text 格式
+    fn sql_copy_in(&self, table: &TableSchema, cols: &[String]) -> String;
+    /// 更新列，exprs 为以 sql 表达式设置的列，参数依次为 cols 的值、exprs 的参数、where 的参数
     fn sql_update_columns(
         &self,
         table: &TableSchema,
         cols: &Vec<String>,
+        exprs: &[SetExpr],
         wh: Option<Where>,
     ) -> String;
 
     fn sql_delete(&self, table: &TableSchema, wh: Option<Where>) -> String;
-    fn sql_count(&self, table: &TableSchema, wh: Option<Where>) -> String;
+    fn sql_count(
+        &self,
+        table: &TableSchema,
+        wh: Option<Where>,
+        distinct: Option<&Distinct>,
+    ) -> String;
     
     fn sql_select(
         &self,
@@ -124,7 +148,10 @@ pub trait Schema
         wh: Option<Where>,
         orders: &Vec<Order>,
         pg: Option<&PageRequest>,
+        lock: Option<&RowLock>,
+        distinct: Option<&Distinct>,
     ) -> String;
+    #[allow(clippy::too_many_arguments)]
     fn sql_select_columns(
         &self,
         table: &TableSchema,
@@ -132,5 +159,7 @@ pub trait Schema
         wh: Option<Where>,
         orders: &Vec<Order>,
         pg: Option<&PageRequest>,
+        lock: Option<&RowLock>,
+        distinct: Option<&Distinct>,
     ) -> String;
 }
@@ -0,0 +1,89 @@
+use super::{
+    builder::{
+        delete_builder::DeleteBuilder, insert_builder::InsertBuilder,
+        select_builder::SelectBuilder, update_builder::UpdateBuilder,
+    },
+    schema::table::TableSchema,
+};
+
+/// 数据表实体，由 #[derive(Table)] 实现，用于编写与具体表无关的通用代码
+/// ```rust,ignore
+/// async fn load<T: Entity>(conn: &mut PgConnection, id: T::Id) -> sqlx::Result<T>
+/// where
+///     for<'r> T: FromRow<'r, PgRow> + Send + Unpin,
+/// {
+///     T::select_by_id(id).one(conn).await
+/// }
+/// ```
+pub trait Entity: Sized {
+    /// 主键类型，联合主键为元组，没有主键时为 ()
+    /// 没有主键时根据主键操作的方法没有意义，Repository 会返回错误
+    type Id;
+
+    /// 缓存的表结构定义
+    fn table_schema() -> &'static TableSchema;
+
+    /// 主键值
+    fn id(&self) -> Self::Id;
+
+    /// 是否为未插入的新记录，自增主键为默认值（如 0）时为 true
+    /// 没有自增主键时无法判断，始终为 false
+    fn is_new(&self) -> bool;
+
+    fn select<'a>() -> SelectBuilder<'a, Self>;
+
+    /// 根据主键查询
+    fn select_by_id<'a>(id: Self::Id) -> SelectBuilder<'a, Self>;
+
+    fn insert<'a>(&self) -> InsertBuilder<'a, Self>;
+
+    /// 插入全部数据，主键冲突时更新其它列，没有主键的表执行时返回错误
+    /// 自增列由数据库生成，不检查乐观锁版本
+    fn upsert<'a>(&self) -> InsertBuilder<'a, Self>;
+
+    /// 根据主键更新全部数据
+    fn update<'a>(&self) -> UpdateBuilder<'a, Self>;
+
+    /// 根据主键删除
+    fn delete<'a>(&self) -> DeleteBuilder<'a, Self>;
+
+    /// 根据主键删除
+    fn delete_by_id<'a>(id: Self::Id) -> DeleteBuilder<'a, Self>;
+}
+
+/// 一组实体，用于同步多个表结构
+/// ```rust,ignore
+/// sync::<(User, Message)>(&mut conn).await?;
+/// ```
+pub trait Entities {
+    fn tables() -> Vec<TableSchema>;
+}
+
+impl<T: Entity> Entities for T {
+    fn tables() -> Vec<TableSchema> {
+        vec![T::table_schema().clone()]
+    }
+}
+
+macro_rules! impl_entities_for_tuple {
+    ($($t:ident)+) => {
+        impl<$($t: Entity),+> Entities for ($($t,)+) {
+            fn tables() -> Vec<TableSchema> {
+                vec![$($t::table_schema().clone()),+]
+            }
+        }
+    };
+}
+
+impl_entities_for_tuple!(A);
+impl_entities_for_tuple!(A B);
+impl_entities_for_tuple!(A B C);
+impl_entities_for_tuple!(A B C D);
+impl_entities_for_tuple!(A B C D E);
+impl_entities_for_tuple!(A B C D E F);
+impl_entities_for_tuple!(A B C D E F G);
+impl_entities_for_tuple!(A B C D E F G H);
+impl_entities_for_tuple!(A B C D E F G H I);
+impl_entities_for_tuple!(A B C D E F G H I J);
+impl_entities_for_tuple!(A B C D E F G H I J K);
+impl_entities_for_tuple!(A B C D E F G H I J K L);
@@ -2,5 +2,9 @@ pub mod schema;
 pub mod dialects;
 pub mod builder;
 pub mod utils;
+pub mod entity;
+pub mod typed;
+#[cfg(feature = "postgres")]
+pub mod repository;
 
 // pub fn new() -> schema::S
\ No newline at end of file
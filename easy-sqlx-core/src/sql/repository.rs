@@ -0,0 +1,186 @@
+use std::marker::PhantomData;
+
+use sqlx::{
+    postgres::{PgQueryResult, PgRow},
+    Error, FromRow, PgPool,
+};
+
+use super::{
+    builder::{
+        builder::{ExecuteBuilder as _, QueryBuilder as _},
+        error::BuilderError,
+    },
+    dialects::{
+        condition::{Condition, WhereAppend as _},
+        page::{Order, PageRequest, PageResult},
+    },
+    entity::Entity,
+    typed::Typed,
+};
+
+/// 通用的数据访问对象，适用于任何 #[derive(Table)] 的结构体
+/// ```rust,ignore
+/// let users = Repository::<User>::new(pool.clone());
+/// let user = users.find_by_id(1).await?;
+/// users.save(&user).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Repository<T> {
+    pool: PgPool,
+    default_schema: String,
+    entity: PhantomData<T>,
+}
+
+impl<T> Repository<T>
+where
+    T: Entity,
+    for<'r> T: FromRow<'r, PgRow>,
+    T: Send + Unpin,
+{
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            default_schema: "".to_string(),
+            entity: PhantomData,
+        }
+    }
+
+    pub fn with_default_schema<S: Into<String>>(mut self, schema: S) -> Self {
+        self.default_schema = schema.into();
+        self
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// 根据主键操作前检查表是否有主键，没有主键时 select_by_id 等会作用于全部记录
+    fn check_pk() -> Result<(), Error> {
+        let table = T::table_schema();
+        if table.columns.iter().any(|c| c.pk) {
+            return Ok(());
+        }
+        Err(Error::Configuration(
+            format!("table {} has no primary key", table.name).into(),
+        ))
+    }
+
+    /// 根据主键查询，不存在时返回 None
+    pub async fn find_by_id(&self, id: T::Id) -> Result<Option<T>, Error> {
+        Self::check_pk()?;
+        T::select_by_id(id)
+            .with_default_schema(&self.default_schema)
+            .optional(&self.pool)
+            .await
+    }
+
+    /// 按条件及排序查询全部记录，wh 为 None 时查询全部
+    /// 条件及排序只接受属于 T 的带类型参数，如 User::ID.gt(1)、User::id_desc()
+    pub async fn find_all(
+        &self,
+        wh: Option<Typed<T, Condition>>,
+        orders: Vec<Typed<T, Order>>,
+    ) -> Result<Vec<T>, Error> {
+        let mut builder = T::select().with_default_schema(&self.default_schema);
+        if let Some(w) = wh {
+            builder = builder.and(w);
+        }
+        for order in orders {
+            builder = builder.order_by(order);
+        }
+        builder.all(&self.pool).await
+    }
+
+    /// 按条件及排序分页查询
+    pub async fn find_page(
+        &self,
+        wh: Option<Typed<T, Condition>>,
+        orders: Vec<Typed<T, Order>>,
+        page: &PageRequest,
+    ) -> Result<PageResult<T>, Error> {
+        let mut builder = T::select().with_default_schema(&self.default_schema);
+        if let Some(w) = wh {
+            builder = builder.and(w);
+        }
+        for order in orders {
+            builder = builder.order_by(order);
+        }
+        builder.page(&self.pool, page).await
+    }
+
+    /// 保存实体，自增主键为默认值的新记录使用 insert 插入，其它记录根据主键 update 全部列
+    /// 有版本列时检查乐观锁，版本不一致返回 BuilderError::StaleVersion
+    /// 没有自增主键的表，update 没有修改记录时改为 insert，主键已存在时返回 update 的错误
+    pub async fn save(&self, entity: &T) -> Result<PgQueryResult, Error> {
+        Self::check_pk()?;
+        let mut conn = self.pool.acquire().await?;
+        if entity.is_new() {
+            return entity
+                .insert()
+                .with_default_schema(&self.default_schema)
+                .execute(&mut *conn)
+                .await;
+        }
+        let updated = entity
+            .update()
+            .with_default_schema(&self.default_schema)
+            .execute(&mut *conn)
+            .await;
+        let autoincr = T::table_schema().columns.iter().any(|c| c.pk && c.autoincr);
+        let missing = match &updated {
+            Ok(result) => result.rows_affected() == 0,
+            Err(err) => matches!(
+                BuilderError::from_sqlx(err),
+                Some(BuilderError::StaleVersion(_))
+            ),
+        };
+        if autoincr || !missing {
+            return updated;
+        }
+        let inserted = entity
+            .insert()
+            .with_default_schema(&self.default_schema)
+            .execute(&mut *conn)
+            .await;
+        match inserted {
+            // 记录存在但版本不一致
+            Err(err) if updated.is_err() && is_unique_violation(&err) => updated,
+            inserted => inserted,
+        }
+    }
+
+    /// 根据主键删除，设置了 soft_delete 的表为软删除
+    pub async fn delete_by_id(&self, id: T::Id) -> Result<PgQueryResult, Error> {
+        Self::check_pk()?;
+        let mut conn = self.pool.acquire().await?;
+        T::delete_by_id(id)
+            .with_default_schema(&self.default_schema)
+            .execute(&mut *conn)
+            .await
+    }
+
+    /// 主键对应的记录是否存在
+    pub async fn exists(&self, id: T::Id) -> Result<bool, Error> {
+        Self::check_pk()?;
+        let count = T::select_by_id(id)
+            .with_default_schema(&self.default_schema)
+            .count(&self.pool)
+            .await?;
+        Ok(count > 0)
+    }
+
+    /// 统计满足条件的记录数，wh 为 None 时统计全部
+    pub async fn count(&self, wh: Option<Typed<T, Condition>>) -> Result<usize, Error> {
+        let mut builder = T::select().with_default_schema(&self.default_schema);
+        if let Some(w) = wh {
+            builder = builder.and(w);
+        }
+        builder.count(&self.pool).await
+    }
+}
+
+/// 是否为唯一约束冲突
+fn is_unique_violation(err: &Error) -> bool {
+    err.as_database_error()
+        .is_some_and(|err| err.is_unique_violation())
+}
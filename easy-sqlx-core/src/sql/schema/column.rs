@@ -28,6 +28,18 @@ pub struct Column {
     pub autoincr: bool,
     /// 说明信息
     pub comment: Option<String>,
+    /// [代码生成控制]
+    /// 乐观锁版本列 version，只能用于整数类型
+    /// 生成的 update 以版本作为条件并将版本加 1，没有更新任何记录时返回 BuilderError::StaleVersion
+    pub version: bool,
+    /// [代码生成控制]
+    /// 创建时间列 created_at，只能用于 NaiveDateTime 类型
+    /// 生成的 insert 使用当前时间，生成的 update 不修改该列
+    pub created_at: bool,
+    /// [代码生成控制]
+    /// 修改时间列 updated_at，只能用于 NaiveDateTime 类型
+    /// 生成的 insert 及 UpdateBuilder 自动设置为当前时间
+    pub updated_at: bool,
 
     /// 是否可为空 null | not-null
     pub nullable: bool,
@@ -126,6 +138,15 @@ impl Column {
         if !self.replace {
             self.replace = source.replace;
         }
+        if !self.version {
+            self.version = source.version;
+        }
+        if !self.created_at {
+            self.created_at = source.created_at;
+        }
+        if !self.updated_at {
+            self.updated_at = source.updated_at;
+        }
         // self.typ = source.typ.clone();
     }
 }
@@ -156,6 +177,9 @@ impl ToTokens for Column {
         let has_from = !from.is_empty();
 
         let replace = self.replace;
+        let version = self.version;
+        let created_at = self.created_at;
+        let updated_at = self.updated_at;
 
         // let col_type =
         // let has_len2 = self.len2.is_some();
@@ -175,6 +199,9 @@ impl ToTokens for Column {
                 default: if #has_default { Some(#default.to_string()) } else { None },
                 from: if #has_from { Some(#from.to_string()) } else { None },
                 replace: #replace,
+                version: #version,
+                created_at: #created_at,
+                updated_at: #updated_at,
                 // typ:
                 // len2: if #has_len2 { Some(#len2) } else { None },
                 ..Default::default()
@@ -195,7 +222,7 @@ impl ToTokens for Column {
 impl Parse for Column {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         const EXPECTED_ATTRIBUTE: &str =
-            "unexpected attribute, expected any of: ignore, pk, column, len, col_type, autoincr, comment, default, from, replace";
+            "unexpected attribute, expected any of: ignore, pk, column, len, col_type, autoincr, comment, default, from, replace, version, created_at, updated_at";
 
         let mut column = Column::default();
 
@@ -252,6 +279,15 @@ impl Parse for Column {
                 "replace" => {
                     column.replace = true;
                 }
+                "version" => {
+                    column.version = true;
+                }
+                "created_at" => {
+                    column.created_at = true;
+                }
+                "updated_at" => {
+                    column.updated_at = true;
+                }
                 "len" => {
                     column.typ.len = Some(
                         parse_next(input, || input.parse::<LitInt>())
@@ -292,6 +328,18 @@ impl Parse for Column {
                             .value(),
                     );
                 }
+                "default" => {
+                    column.default = Some(
+                        parse_next(input, || input.parse::<LitStr>())
+                            .map_err(|err| {
+                                Error::new(
+                                    err.span(),
+                                    format!("attribute {attribute} parse error, {err}"),
+                                )
+                            })?
+                            .value(),
+                    );
+                }
                 "comment" => {
                     column.comment = Some(
                         parse_next(input, || input.parse::<LitStr>())
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use easy_sqlx_utils::value_parser::{parse_groups, parse_next, Array};
 use proc_macro2::Span;
 use quote::{quote, ToTokens};
@@ -33,6 +35,15 @@ pub struct TableSchema {
     /// [控制字段]
     /// 删除未定义的索引
     pub trim_indexes: bool,
+    /// [代码生成控制]
+    /// 软删除列，必须是可为空的时间列
+    /// 设置后删除记录时将该列设置为当前时间，查询时自动过滤该列不为空的记录
+    /// 解析完成后保存的是数据库列名称
+    pub soft_delete: Option<String>,
+    /// [代码生成控制]
+    /// 生成 sqlx::FromRow 的实现，按数据库列名称读取字段，忽略的字段使用 Default
+    /// 设置后结构体不能再 #[derive(FromRow)]
+    pub from_row: bool,
 }
 
 impl TableSchema {
@@ -68,6 +79,33 @@ impl TableSchema {
         Ok(())
     }
 
+    /// 检查软删除列是否合法，并将其转换为数据库列名称
+    pub fn check_soft_delete(&mut self) -> syn::Result<()> {
+        if let Some(name) = self.soft_delete.as_ref() {
+            let col = self.find_column(name).ok_or_else(|| {
+                Error::new(
+                    Span::call_site(),
+                    format!("Soft delete column '{name}' is not exists in table columns"),
+                )
+            })?;
+            if !col.nullable {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!("Soft delete column '{name}' must be nullable"),
+                ));
+            }
+            self.soft_delete = Some(col.get_column_name());
+        }
+        Ok(())
+    }
+
+    /// 是否为软删除列
+    pub fn is_soft_delete(&self, col: &Column) -> bool {
+        self.soft_delete
+            .as_ref()
+            .is_some_and(|name| *name == col.get_column_name())
+    }
+
     pub fn find_column(&self, name: &String) -> Option<Column> {
         self.columns
             .iter()
@@ -109,6 +147,14 @@ impl TableSchema {
             self.trim_indexes = source.trim_indexes;
         }
 
+        if self.soft_delete.is_none() {
+            self.soft_delete = source.soft_delete.clone();
+        }
+
+        if !self.from_row {
+            self.from_row = source.from_row;
+        }
+
         if let Some(src_indexes) = source.indexes {
             for idx in src_indexes {
                 if self.indexes.is_none() {
@@ -165,6 +211,20 @@ impl TableSchema {
     }
 }
 
+/// builder 持有 Cow<TableSchema>，既可以传入 TableSchema，
+/// 也可以借用生成的 table_schema() 返回的静态定义，避免每次查询复制表结构
+impl From<TableSchema> for Cow<'_, TableSchema> {
+    fn from(table: TableSchema) -> Self {
+        Cow::Owned(table)
+    }
+}
+
+impl<'a> From<&'a TableSchema> for Cow<'a, TableSchema> {
+    fn from(table: &'a TableSchema) -> Self {
+        Cow::Borrowed(table)
+    }
+}
+
 impl ToTokens for TableSchema {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let name = self.name.clone();
@@ -181,6 +241,9 @@ impl ToTokens for TableSchema {
         let trim_indexes = self.trim_indexes;
         let recreate = self.recreate.clone().unwrap_or("".to_string());
         let has_recreate = !recreate.is_empty();
+        let soft_delete = self.soft_delete.clone().unwrap_or("".to_string());
+        let has_soft_delete = !soft_delete.is_empty();
+        let from_row = self.from_row;
         quote! {
             easy_sqlx_core::sql::schema::table::TableSchema {
                 indexes: if #has_idxs { Some([#(#idxs), *].to_vec()) } else { None },
@@ -192,6 +255,8 @@ impl ToTokens for TableSchema {
                 recreate: if #has_recreate { Some(#recreate.to_string()) } else { None },
                 trim_columns: #trim_columns,
                 trim_indexes: #trim_indexes,
+                soft_delete: if #has_soft_delete { Some(#soft_delete.to_string()) } else { None },
+                from_row: #from_row,
                 // raw_indexes: if #has_raw_idxs { Some([#(#raw_idxs), *].to_vec()) } else { None },
             }
         }
@@ -202,7 +267,7 @@ impl ToTokens for TableSchema {
 impl Parse for TableSchema {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         const EXPECTED_ATTRIBUTE: &str =
-            "unexpected attribute, expected any of: name, comment, schema, from, recreate, trim_columns, trim_indexes indexes[]";
+            "unexpected attribute, expected any of: name, comment, schema, from, recreate, trim_columns, trim_indexes, soft_delete, from_row, indexes[]";
 
         let mut table = TableSchema::default();
         let mut idxes = vec![];
@@ -241,6 +306,9 @@ impl Parse for TableSchema {
                 "trim_indexes" => {
                     table.trim_indexes = true;
                 }
+                "from_row" => {
+                    table.from_row = true;
+                }
                 "recreate" => {
                     table.recreate = Some(
                         parse_next(input, || input.parse::<LitStr>())
@@ -253,6 +321,18 @@ impl Parse for TableSchema {
                             .value(),
                     );
                 }
+                "soft_delete" => {
+                    table.soft_delete = Some(
+                        parse_next(input, || input.parse::<LitStr>())
+                            .map_err(|err| {
+                                Error::new(
+                                    err.span(),
+                                    format!("attribute {attribute} parse error, {err}"),
+                                )
+                            })?
+                            .value(),
+                    );
+                }
                 "comment" => {
                     table.comment = Some(
                         parse_next(input, || input.parse::<LitStr>())
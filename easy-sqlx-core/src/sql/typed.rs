@@ -0,0 +1,247 @@
+use std::marker::PhantomData;
+
+use super::{
+    dialects::{
+        condition::{Condition, Operator},
+        page::Order,
+    },
+    utils::{pair::Pair, value::Value},
+};
+
+/// 带类型的列，由 #[derive(Table)] 为每个字段生成常量，如 User::ID
+/// E 为列所属的实体，T 为条件使用的值类型，F 为字段类型（Option 字段为 Option<T>）
+/// 生成的条件、排序、赋值只能用于同一实体的 builder，值只能是字段的类型
+/// ```rust,ignore
+/// User::select()
+///     .and(User::ID.gt(10))
+///     .and(User::NAME.like("%abc%".to_string()))
+///     .order_by(User::ID.desc())
+/// ```
+pub struct Column<E, T, F = T> {
+    name: &'static str,
+    entity: ColumnMarker<E, T, F>,
+}
+
+/// 不持有任何值，Column 总是 Copy 并且不受 E、T、F 的 Send、Sync 影响
+type ColumnMarker<E, T, F> = PhantomData<fn() -> (E, T, F)>;
+
+impl<E, T, F> Clone for Column<E, T, F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E, T, F> Copy for Column<E, T, F> {}
+
+impl<E, T, F> std::fmt::Debug for Column<E, T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Column").field(&self.name).finish()
+    }
+}
+
+impl<E, T, F> Column<E, T, F> {
+    /// name 为数据库列名称
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            entity: PhantomData,
+        }
+    }
+
+    /// 数据库列名称
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn condition(&self, value: Value, op: Operator) -> Typed<E, Condition> {
+        Typed::new(Condition::Condition(
+            Pair {
+                name: self.name.to_string(),
+                value,
+            },
+            op,
+        ))
+    }
+
+    /// 升序
+    pub fn asc(&self) -> Typed<E, Order> {
+        Typed::new(Order::asc(self.name.to_string()))
+    }
+
+    /// 降序
+    pub fn desc(&self) -> Typed<E, Order> {
+        Typed::new(Order::desc(self.name.to_string()))
+    }
+
+    /// 与同一实体中相同值类型的列比较，如 "updated_at" > "created_at"
+    pub fn compare<G>(&self, op: Operator, other: Column<E, T, G>) -> Typed<E, Condition> {
+        Typed::new(Condition::columns(self.name, op, other.name))
+    }
+}
+
+/// 可为空的列（Option 字段）
+impl<E, T> Column<E, T, Option<T>> {
+    /// is null
+    pub fn is_null(&self) -> Typed<E, Condition> {
+        self.condition(Value::Text(None), Operator::IsNull)
+    }
+
+    /// is not null
+    pub fn is_not_null(&self) -> Typed<E, Condition> {
+        self.condition(Value::Text(None), Operator::IsNotNull)
+    }
+}
+
+impl<E, T: Into<Value>, F> Column<E, T, F> {
+    /// =
+    pub fn eq(&self, value: T) -> Typed<E, Condition> {
+        self.condition(value.into(), Operator::Eq)
+    }
+
+    /// <>
+    pub fn neq(&self, value: T) -> Typed<E, Condition> {
+        self.condition(value.into(), Operator::Neq)
+    }
+
+    /// >
+    pub fn gt(&self, value: T) -> Typed<E, Condition> {
+        self.condition(value.into(), Operator::Gt)
+    }
+
+    /// >=
+    pub fn ge(&self, value: T) -> Typed<E, Condition> {
+        self.condition(value.into(), Operator::Ge)
+    }
+
+    /// <
+    pub fn lt(&self, value: T) -> Typed<E, Condition> {
+        self.condition(value.into(), Operator::Lt)
+    }
+
+    /// <=
+    pub fn le(&self, value: T) -> Typed<E, Condition> {
+        self.condition(value.into(), Operator::Le)
+    }
+}
+
+impl<E, T, F> Column<E, T, F>
+where
+    Vec<T>: Into<Value>,
+{
+    /// in (...)，空列表恒为假
+    pub fn is_in(&self, values: Vec<T>) -> Typed<E, Condition> {
+        self.condition(values.into(), Operator::In)
+    }
+
+    /// not in (...)，空列表恒为真
+    pub fn not_in(&self, values: Vec<T>) -> Typed<E, Condition> {
+        self.condition(values.into(), Operator::NotIn)
+    }
+}
+
+impl<E, F> Column<E, String, F> {
+    /// like
+    pub fn like(&self, value: String) -> Typed<E, Condition> {
+        self.condition(value.into(), Operator::Like)
+    }
+}
+
+impl<E, T, F: Into<Value>> Column<E, T, F> {
+    /// 赋值，用于 insert 及 update
+    pub fn set(&self, value: F) -> Typed<E, Pair> {
+        Typed::new(Pair {
+            name: self.name.to_string(),
+            value: value.into(),
+        })
+    }
+}
+
+/// 属于实体 E 的条件、排序或赋值
+pub struct Typed<E, V> {
+    inner: V,
+    entity: PhantomData<fn() -> E>,
+}
+
+impl<E, V: Clone> Clone for Typed<E, V> {
+    fn clone(&self) -> Self {
+        Typed::new(self.inner.clone())
+    }
+}
+
+impl<E, V: std::fmt::Debug> std::fmt::Debug for Typed<E, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Typed").field(&self.inner).finish()
+    }
+}
+
+impl<E, V> Typed<E, V> {
+    fn new(inner: V) -> Self {
+        Self {
+            inner,
+            entity: PhantomData,
+        }
+    }
+
+    /// 将无类型的值标记为属于实体 E，不检查列是否属于 E 及值的类型
+    /// 优先使用带类型的列，如 User::NAME.eq(..)，只在原始 sql 条件、表达式排序等无法使用带类型的列时使用
+    /// ```rust,ignore
+    /// User::select().and(Typed::unchecked(Condition::raw("age > $1", vec![Value::from(18)])))
+    /// ```
+    pub fn unchecked(inner: V) -> Self {
+        Self::new(inner)
+    }
+
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+}
+
+impl<E> Typed<E, Condition> {
+    /// 与同一实体的条件 and
+    pub fn and(self, other: Typed<E, Condition>) -> Self {
+        Typed::new(Condition::And(
+            Box::new(self.inner),
+            Box::new(other.inner),
+        ))
+    }
+
+    /// 与同一实体的条件 or
+    pub fn or(self, other: Typed<E, Condition>) -> Self {
+        Typed::new(Condition::Or(
+            Box::new(self.inner),
+            Box::new(other.inner),
+        ))
+    }
+}
+
+impl<E> Typed<E, Order> {
+    /// 空值排在最前
+    pub fn nulls_first(self) -> Self {
+        Typed::new(self.inner.nulls_first())
+    }
+
+    /// 空值排在最后
+    pub fn nulls_last(self) -> Self {
+        Typed::new(self.inner.nulls_last())
+    }
+}
+
+/// builder 可以接受的参数
+/// 实体 E 的 builder（for_entity 及 #[derive(Table)] 生成的函数创建）只接受属于 E 的 Typed<E, V>，
+/// 使用 new 创建的无类型 builder（E 为 ()）接受无类型的 V
+/// 需要在实体 builder 中使用原始 sql 条件等无类型参数时，使用 Typed::unchecked 显式包装
+pub trait EntityArg<E, V> {
+    fn into_arg(self) -> V;
+}
+
+impl<V> EntityArg<(), V> for V {
+    fn into_arg(self) -> V {
+        self
+    }
+}
+
+impl<E, V> EntityArg<E, V> for Typed<E, V> {
+    fn into_arg(self) -> V {
+        self.inner
+    }
+}
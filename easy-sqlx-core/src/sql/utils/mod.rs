@@ -1,3 +1,4 @@
 pub mod pair;
 pub mod value;
-pub mod quote;
\ No newline at end of file
+pub mod quote;
+pub mod placeholder;
\ No newline at end of file
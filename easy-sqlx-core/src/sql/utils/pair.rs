@@ -1,3 +1,5 @@
+use crate::sql::dialects::context::Context;
+
 use super::value::Value;
 // impl Value {
 //     pub fn get<T: I32, I64, F64, String>(&self) -> T {
@@ -16,6 +18,46 @@ pub struct Pair {
     pub value: Value,
 }
 
+/// update 语句中以 sql 表达式设置的列: 列 = 表达式
+/// 表达式中的参数占位符从 $1 开始编号，生成 sql 时自动顺延
+/// 数组参数展开为多个绑定参数，表达式中需要为每个元素编写占位符
+#[derive(Debug, Clone)]
+pub struct SetExpr {
+    pub name: String,
+    pub expr: String,
+    pub params: Vec<Value>,
+}
+
+impl SetExpr {
+    /// ```rust,ignore
+    /// // "updated_at" = now()
+    /// SetExpr::new("updated_at", "now()", vec![])
+    /// // "score" = "score" * $1 + $2
+    /// SetExpr::new("score", r#""score" * $1 + $2"#, vec![Value::from(2), Value::from(1)])
+    /// ```
+    pub fn new(name: impl Into<String>, expr: impl Into<String>, params: Vec<Value>) -> Self {
+        Self {
+            name: name.into(),
+            expr: expr.into(),
+            params,
+        }
+    }
+
+    /// 列 = coalesce(列, 0) + 值
+    pub fn incr(name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let name = name.into();
+        let expr = format!("coalesce({}, 0) + $1", Context::default().quote(&name));
+        Self::new(name, expr, vec![value.into()])
+    }
+
+    /// 列 = coalesce(列, 0) - 值
+    pub fn decr(name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let name = name.into();
+        let expr = format!("coalesce({}, 0) - $1", Context::default().quote(&name));
+        Self::new(name, expr, vec![value.into()])
+    }
+}
+
 impl Pair {
     // pub fn bind_to_query<'a, DB: Database>(
     //     &self,
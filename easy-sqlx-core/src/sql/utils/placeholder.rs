@@ -0,0 +1,64 @@
+/// 将 sql 片段中从 $1 开始编号的参数占位符顺延到从 param_index 开始，
+/// 单引号字符串和双引号标识符中的内容保持不变
+///
+/// 例如: shift_placeholders("lower(name) = $1", 3) => "lower(name) = $3"
+pub fn shift_placeholders(sql: &str, param_index: usize) -> String {
+    map_placeholders(sql, |n| n + param_index - 1)
+}
+
+/// sql 片段中最大的参数占位符编号，没有占位符时为 0
+pub fn max_placeholder(sql: &str) -> usize {
+    let mut max = 0;
+    map_placeholders(sql, |n| {
+        max = max.max(n);
+        n
+    });
+    max
+}
+
+/// 按 f 替换引号外的 $n 占位符编号
+fn map_placeholders<F: FnMut(usize) -> usize>(sql: &str, mut f: F) -> String {
+    let mut buf = String::with_capacity(sql.len());
+    let mut quote: Option<char> = None;
+    let mut chars = sql.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if let Some(q) = quote {
+            if ch == q {
+                quote = None;
+            }
+            buf.push(ch);
+            continue;
+        }
+
+        if ch == '\'' || ch == '"' {
+            quote = Some(ch);
+            buf.push(ch);
+            continue;
+        }
+
+        if ch == '$' {
+            let mut digits = String::new();
+            while let Some(d) = chars.peek() {
+                if !d.is_ascii_digit() {
+                    break;
+                }
+                digits.push(*d);
+                chars.next();
+            }
+            match digits.parse::<usize>() {
+                Ok(n) if n > 0 => {
+                    buf.push_str(format!("${}", f(n)).as_str());
+                }
+                _ => {
+                    buf.push(ch);
+                    buf.push_str(&digits);
+                }
+            }
+            continue;
+        }
+
+        buf.push(ch);
+    }
+    buf
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Bool(Option<bool>),
     // Byte(Option<i8>),
@@ -18,6 +18,11 @@ use chrono::NaiveDateTime;
 use sqlx::Database;
 
 impl Value {
+    /// 当前本地时间，用于自动设置的时间列
+    pub fn now() -> Self {
+        Value::ChronoDate(Some(chrono::Local::now().naive_local()))
+    }
+
     pub fn len(&self) -> usize {
         match self {
             Value::Array(ary) => ary.len(),
@@ -25,6 +30,46 @@ impl Value {
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 是否为空值，数组不视为空值
+    pub fn is_null(&self) -> bool {
+        match self {
+            Value::Bool(v) => v.is_none(),
+            Value::Binary(v) => v.is_none(),
+            Value::Short(v) => v.is_none(),
+            Value::Int(v) => v.is_none(),
+            Value::Long(v) => v.is_none(),
+            Value::Float(v) => v.is_none(),
+            Value::Double(v) => v.is_none(),
+            Value::Text(v) => v.is_none(),
+            Value::ChronoDate(v) => v.is_none(),
+            Value::Array(_) => false,
+        }
+    }
+
+    /// 实际绑定的参数个数，数组按元素展开，与 append_params 一致
+    pub fn bind_count(&self) -> usize {
+        match self {
+            Value::Array(ary) => ary.iter().map(|v| v.bind_count()).sum(),
+            _ => 1,
+        }
+    }
+
+    /// 按绑定顺序追加参数，数组展开为多个参数，与 bind_to_query 一致
+    pub fn append_params(&self, params: &mut Vec<Value>) {
+        match self {
+            Value::Array(ary) => {
+                for val in ary {
+                    val.append_params(params);
+                }
+            }
+            _ => params.push(self.clone()),
+        }
+    }
+
     pub fn bind_to_query<'a, DB: Database>(
         &self,
         query: sqlx::query::Query<'a, DB, DB::Arguments<'a>>,
@@ -164,6 +209,33 @@ impl Value {
     }
 }
 
+#[cfg(feature = "postgres")]
+impl Value {
+    /// 按列的数据类型从查询结果中读取指定列的值
+    pub fn from_pg_row(row: &sqlx::postgres::PgRow, name: &str) -> Result<Self, sqlx::Error> {
+        use sqlx::{Column as _, Row as _, TypeInfo as _};
+
+        let type_name = row.try_column(name)?.type_info().name().to_string();
+        let value = match type_name.as_str() {
+            "BOOL" => Value::Bool(row.try_get(name)?),
+            "INT2" => Value::Short(row.try_get(name)?),
+            "INT4" => Value::Int(row.try_get(name)?),
+            "INT8" => Value::Long(row.try_get(name)?),
+            "FLOAT4" => Value::Float(row.try_get(name)?),
+            "FLOAT8" => Value::Double(row.try_get(name)?),
+            "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => Value::Text(row.try_get(name)?),
+            "TIMESTAMP" => Value::ChronoDate(row.try_get(name)?),
+            "BYTEA" => Value::Binary(row.try_get(name)?),
+            _ => {
+                return Err(sqlx::Error::Decode(
+                    format!("unsupported column type {type_name} of column {name}").into(),
+                ))
+            }
+        };
+        Ok(value)
+    }
+}
+
 // impl From<Vec<u8>> for Value {
 //     fn from(value: Vec<u8>) -> Self {
 //         Self::Binary(value)
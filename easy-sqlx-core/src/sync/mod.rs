@@ -7,6 +7,7 @@ use crate::sql::{
         self,
         schema::schema::{self, Schema},
     },
+    entity::Entities,
     schema::table::TableSchema,
 };
 
@@ -40,6 +41,24 @@ where
     sync_tables_with_schema(conn, tables, "").await
 }
 
+/// 同步一组实体的表结构
+/// ```rust,ignore
+/// sync::<(User, Message)>(&mut conn).await?;
+/// ```
+#[cfg(feature = "postgres")]
+pub async fn sync<E: Entities>(conn: &mut sqlx::PgConnection) -> io::Result<()> {
+    sync_tables(conn, E::tables()).await
+}
+
+/// 同步一组实体的表结构，default_schema 为没有设置 schema 的表使用的 schema
+#[cfg(feature = "postgres")]
+pub async fn sync_with_schema<E: Entities>(
+    conn: &mut sqlx::PgConnection,
+    default_schema: &str,
+) -> io::Result<()> {
+    sync_tables_with_schema(conn, E::tables(), default_schema).await
+}
+
 pub async fn sync_tables_with_schema<C, DB: Database>(
     conn: &mut C,
     tables: Vec<TableSchema>,
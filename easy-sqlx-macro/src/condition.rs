@@ -19,14 +19,14 @@ fn create_condition_func(
         let comment = format!("// {}", op.sql());
         return quote! {
             #[doc = #comment]
-            pub fn #in_name() -> easy_sqlx_core::sql::dialects::condition::Condition {
+            pub fn #in_name() -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::dialects::condition::Condition> {
                 let val: Option<#syn_type> = None;
                 let pair = easy_sqlx_core::sql::utils::pair::Pair {
                     name: #col_name.to_string(),
                     value: easy_sqlx_core::sql::utils::value::Value::from(val)
                 };
                 let op = easy_sqlx_core::sql::dialects::condition::Operator::resolve(#oper.to_string());
-                easy_sqlx_core::sql::dialects::condition::Condition::Condition(pair, op)
+                easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::dialects::condition::Condition::Condition(pair, op))
             }
         };
     }
@@ -34,13 +34,13 @@ fn create_condition_func(
     let comment = format!("// {}", op.sql());
     quote! {
         #[doc = #comment]
-        pub fn #in_name(val: #syn_type) -> easy_sqlx_core::sql::dialects::condition::Condition {
+        pub fn #in_name(val: #syn_type) -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::dialects::condition::Condition> {
             let pair = easy_sqlx_core::sql::utils::pair::Pair {
                 name: #col_name.to_string(),
                 value: easy_sqlx_core::sql::utils::value::Value::from(val)
             };
             let op = easy_sqlx_core::sql::dialects::condition::Operator::resolve(#oper.to_string());
-            easy_sqlx_core::sql::dialects::condition::Condition::Condition(pair, op)
+            easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::dialects::condition::Condition::Condition(pair, op))
         }
     }
 }
@@ -60,16 +60,27 @@ pub fn create_conditions(
 
     // in 操作
     let in_name = syn::Ident::new(format!("{}_in", &field_name).as_str(), Span::call_site());
+    let not_in_name = syn::Ident::new(format!("{}_not_in", &field_name).as_str(), Span::call_site());
 
     if !is_vec {
         conditions.push(quote! {
-            /// in (...)
-            pub fn #in_name(val: Vec<#syn_type>) -> easy_sqlx_core::sql::dialects::condition::Condition {
+            /// in (...)，空列表恒为假
+            pub fn #in_name(val: Vec<#syn_type>) -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::dialects::condition::Condition> {
                 let pair = easy_sqlx_core::sql::utils::pair::Pair {
                     name: #col_name.to_string(),
                     value: easy_sqlx_core::sql::utils::value::Value::from(val)
                 };
-                easy_sqlx_core::sql::dialects::condition::Condition::Condition(pair, easy_sqlx_core::sql::dialects::condition::Operator::In)
+                easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::dialects::condition::Condition::Condition(pair, easy_sqlx_core::sql::dialects::condition::Operator::In))
+            }
+        });
+        conditions.push(quote! {
+            /// not in (...)，空列表恒为真
+            pub fn #not_in_name(val: Vec<#syn_type>) -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::dialects::condition::Condition> {
+                let pair = easy_sqlx_core::sql::utils::pair::Pair {
+                    name: #col_name.to_string(),
+                    value: easy_sqlx_core::sql::utils::value::Value::from(val)
+                };
+                easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::dialects::condition::Condition::Condition(pair, easy_sqlx_core::sql::dialects::condition::Operator::NotIn))
             }
         });
     }
@@ -33,10 +33,10 @@ pub fn create_delete(table: &TableSchema, entity: &Ident) -> proc_macro2::TokenS
     }
     quote! {
         /// 根据主键删除记录
-        pub fn delete<'a>(&self) -> easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a> {
+        pub fn delete<'a>(&self) -> easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a, Self> {
             // let table = &Self::table();
             let #this = self;
-            let mut builder: easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a> = easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder::new(Self::table());
+            let mut builder: easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a, Self> = easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder::for_entity();
             #(#where_args) *
             // }
             builder
@@ -87,8 +87,8 @@ pub fn create_delete_by_id(
     // let note = args.to_string();
     quote! {
         /// 根据 主键 查询
-        pub fn delete_by_id<'a>(#(#id_args), *) -> easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a> {
-            let mut builder: easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a> = easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder::new(Self::table());
+        pub fn delete_by_id<'a>(#(#id_args), *) -> easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a, Self> {
+            let mut builder: easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a, Self> = easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder::for_entity();
             #(#where_args) *
             // }
             builder
@@ -99,8 +99,8 @@ pub fn create_delete_by_id(
 
 pub fn create_delete_builder() -> proc_macro2::TokenStream {
     quote! {
-        pub fn build_delete<'a>() -> easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a> {
-            easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder::new(Self::table())
+        pub fn build_delete<'a>() -> easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a, Self> {
+            easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder::for_entity()
         }
     }
 }
@@ -0,0 +1,104 @@
+use easy_sqlx_core::sql::schema::table::TableSchema;
+use proc_macro2::Ident;
+use quote::quote;
+use syn::Field;
+
+/// 实现 Entity trait，方法调用生成的同名函数
+/// 主键类型: 单个主键为字段类型，联合主键为元组，没有主键为 ()
+pub fn create_entity(
+    table: &TableSchema,
+    entity: &Ident,
+    struct_fields: &[Field],
+) -> proc_macro2::TokenStream {
+    let mut id_names = vec![];
+    let mut id_types = vec![];
+    // 自增主键为默认值时为新记录
+    let mut new_checks = vec![];
+    for (n, col) in table.columns.iter().enumerate() {
+        if col.pk {
+            let name = Ident::new(col.name.as_str(), proc_macro2::Span::call_site());
+            let ty = &struct_fields[n].ty;
+            if col.autoincr {
+                new_checks.push(quote! { self.#name == <#ty as Default>::default() });
+            }
+            id_names.push(name);
+            id_types.push(ty);
+        }
+    }
+    let is_new = if new_checks.is_empty() {
+        quote! { false }
+    } else {
+        quote! { #(#new_checks) || * }
+    };
+
+    let (id_type, id_value, id_bind) = if id_names.len() == 1 {
+        let name = &id_names[0];
+        let ty = id_types[0];
+        (
+            quote! { #ty },
+            quote! { self.#name.clone() },
+            quote! { let #name = id; },
+        )
+    } else {
+        (
+            quote! { (#(#id_types), *) },
+            quote! { (#(self.#id_names.clone()), *) },
+            quote! { let (#(#id_names), *) = id; },
+        )
+    };
+
+    // 没有主键时不生成 upsert 函数，没有冲突目标的 do update 在执行时返回错误
+    let upsert = if id_names.is_empty() {
+        quote! { #entity::insert(self).do_update_excluded(Vec::<String>::new()) }
+    } else {
+        quote! { #entity::upsert(self) }
+    };
+
+    quote! {
+        impl easy_sqlx_core::sql::entity::Entity for #entity {
+            type Id = #id_type;
+
+            fn table_schema() -> &'static easy_sqlx_core::sql::schema::table::TableSchema {
+                #entity::table_schema()
+            }
+
+            fn id(&self) -> Self::Id {
+                #id_value
+            }
+
+            fn is_new(&self) -> bool {
+                #is_new
+            }
+
+            fn select<'a>() -> easy_sqlx_core::sql::builder::select_builder::SelectBuilder<'a, Self> {
+                #entity::select()
+            }
+
+            fn select_by_id<'a>(id: Self::Id) -> easy_sqlx_core::sql::builder::select_builder::SelectBuilder<'a, Self> {
+                #id_bind
+                #entity::select_by_id(#(#id_names), *)
+            }
+
+            fn insert<'a>(&self) -> easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a, Self> {
+                #entity::insert(self)
+            }
+
+            fn upsert<'a>(&self) -> easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a, Self> {
+                #upsert
+            }
+
+            fn update<'a>(&self) -> easy_sqlx_core::sql::builder::update_builder::UpdateBuilder<'a, Self> {
+                #entity::update(self)
+            }
+
+            fn delete<'a>(&self) -> easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a, Self> {
+                #entity::delete(self)
+            }
+
+            fn delete_by_id<'a>(id: Self::Id) -> easy_sqlx_core::sql::builder::delete_builder::DeleteBuilder<'a, Self> {
+                #id_bind
+                #entity::delete_by_id(#(#id_names), *)
+            }
+        }
+    }
+}
@@ -26,42 +26,73 @@ pub fn create_field_wrapper(
     let array_pair = syn::Ident::new(format!("{}_array", &field_name).as_str(), Span::call_site());
     if !is_vec {
         wrappers.push(quote! {
-            pub fn #array_pair(val: Vec<#syn_type>) -> easy_sqlx_core::sql::utils::pair::Pair {
-                easy_sqlx_core::sql::utils::pair::Pair {
+            pub fn #array_pair(val: Vec<#syn_type>) -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::utils::pair::Pair> {
+                easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::Pair {
                     name: #col_name.to_string(),
                     value: easy_sqlx_core::sql::utils::value::Value::from(val)
-                }
+                })
             }
         });
     }
     if col.nullable {
         wrappers.push(quote! {
-            pub fn #field_name(val: #syn_type) -> easy_sqlx_core::sql::utils::pair::Pair {
-                easy_sqlx_core::sql::utils::pair::Pair {
+            pub fn #field_name(val: #syn_type) -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::utils::pair::Pair> {
+                easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::Pair {
                     name: #col_name.to_string(),
                     value: easy_sqlx_core::sql::utils::value::Value::from(val)
-                }
+                })
             }
         });
         let fd2 = syn::Ident::new(format!("{}_opt", &field_name).as_str(), Span::call_site());
         wrappers.push(quote! {
-            pub fn #fd2(val: #ty) -> easy_sqlx_core::sql::utils::pair::Pair {
-                easy_sqlx_core::sql::utils::pair::Pair {
+            pub fn #fd2(val: #ty) -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::utils::pair::Pair> {
+                easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::Pair {
                     name: #col_name.to_string(),
                     value: easy_sqlx_core::sql::utils::value::Value::from(val)
-                }
+                })
             }
         });
     } else {
         wrappers.push(quote! {
-            pub fn #field_name(val: #ty) -> easy_sqlx_core::sql::utils::pair::Pair {
-                easy_sqlx_core::sql::utils::pair::Pair {
+            pub fn #field_name(val: #ty) -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::utils::pair::Pair> {
+                easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::Pair {
                     name: #col_name.to_string(),
                     value: easy_sqlx_core::sql::utils::value::Value::from(val)
-                }
+                })
             }
         });
     }
 
     wrappers
 }
+
+/// 数值类型的列生成自增、自减函数，用于 UpdateBuilder::set_expr
+pub fn create_field_incr(
+    col: &Column,
+    syn_type: &Type,
+    rust_type: &str,
+    is_vec: bool,
+) -> Vec<proc_macro2::TokenStream> {
+    const NUMERIC_TYPES: [&str; 8] = ["i16", "i32", "i64", "u16", "u32", "u64", "f32", "f64"];
+    if is_vec || !NUMERIC_TYPES.contains(&rust_type) {
+        return vec![];
+    }
+    let col_name = &col.get_column_name();
+    let incr = syn::Ident::new(format!("{}_incr", &col.name).as_str(), Span::call_site());
+    let decr = syn::Ident::new(format!("{}_decr", &col.name).as_str(), Span::call_site());
+
+    vec![
+        quote! {
+            /// 列 = coalesce(列, 0) + val
+            pub fn #incr(val: #syn_type) -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::utils::pair::SetExpr> {
+                easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::SetExpr::incr(#col_name, val))
+            }
+        },
+        quote! {
+            /// 列 = coalesce(列, 0) - val
+            pub fn #decr(val: #syn_type) -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::utils::pair::SetExpr> {
+                easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::SetExpr::decr(#col_name, val))
+            }
+        },
+    ]
+}
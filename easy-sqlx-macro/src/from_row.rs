@@ -0,0 +1,47 @@
+use easy_sqlx_core::sql::schema::table::TableSchema;
+use proc_macro2::Ident;
+use quote::quote;
+
+/// 实现 sqlx::FromRow，按数据库列名称读取字段
+/// 生成的查询会将重命名的列别名为字段名称（"abc" as name），列名称不存在时再按字段名称读取
+/// ignored 为 #[col(ignore)] 的字段，使用 Default 填充
+pub fn create_from_row(
+    table: &TableSchema,
+    entity: &Ident,
+    ignored: &Vec<Ident>,
+) -> proc_macro2::TokenStream {
+    if !table.from_row {
+        return quote! {};
+    }
+
+    let mut fields = vec![];
+    for col in table.columns.iter() {
+        let field = Ident::new(col.name.as_str(), proc_macro2::Span::call_site());
+        let col_name = col.get_column_name();
+        let field_name = col.name.clone();
+        if col_name == field_name {
+            fields.push(quote! {
+                #field: row.try_get(#col_name)?
+            });
+        } else {
+            fields.push(quote! {
+                #field: match row.try_get(#col_name) {
+                    Err(easy_sqlx_core::sqlx::Error::ColumnNotFound(_)) => row.try_get(#field_name)?,
+                    value => value?,
+                }
+            });
+        }
+    }
+
+    quote! {
+        impl<'r> easy_sqlx_core::sqlx::FromRow<'r, easy_sqlx_core::sqlx::postgres::PgRow> for #entity {
+            fn from_row(row: &'r easy_sqlx_core::sqlx::postgres::PgRow) -> Result<Self, easy_sqlx_core::sqlx::Error> {
+                use easy_sqlx_core::sqlx::Row as _;
+                Ok(Self {
+                    #(#fields,) *
+                    #(#ignored: Default::default(),) *
+                })
+            }
+        }
+    }
+}
@@ -1,56 +1,252 @@
 use easy_sqlx_core::sql::schema::table::TableSchema;
 use quote::quote;
 
-pub fn create_insert(table: &TableSchema) -> proc_macro2::TokenStream {
-    // insert 绑定参数
-    let this = proc_macro2::Ident::new("this", proc_macro2::Span::call_site());
-    // let mut insert_bind_args: Vec<proc_macro2::TokenStream> = Vec::new();
-    let mut insert_bind_args = vec![];
+/// 生成一行数据的 Value 列表，顺序与 table.columns 一致，receiver 为数据来源变量（如 self）
+fn create_insert_values(
+    table: &TableSchema,
+    receiver: &proc_macro2::TokenStream,
+) -> Vec<proc_macro2::TokenStream> {
+    let mut values = vec![];
     for col in table.columns.iter() {
         let field_name = proc_macro2::Ident::new(col.name.as_str(), proc_macro2::Span::call_site()); // &col.name;
-        let col_name = &col.get_column_name();
         if col.nullable {
-            insert_bind_args.push(quote! {
-                // let #this = self;
-                builder = builder.set(easy_sqlx_core::sql::utils::pair::Pair {
-                    name: #col_name.to_string(),
-                    value: easy_sqlx_core::sql::utils::value::Value::from(self.#field_name.clone()),
-                });
+            values.push(quote! {
+                easy_sqlx_core::sql::utils::value::Value::from(#receiver.#field_name.clone())
             });
         } else {
-            insert_bind_args.push(quote! {
-                // let #this = self;
-                builder = builder.set(easy_sqlx_core::sql::utils::pair::Pair {
+            values.push(quote! {
+                easy_sqlx_core::sql::utils::value::Value::from(&#receiver.#field_name.clone())
+            });
+        }
+    }
+    values
+}
+
+/// 有创建时间或修改时间列时，生成当前时间变量 now，同一语句中的时间列使用相同的值
+fn create_now(table: &TableSchema) -> proc_macro2::TokenStream {
+    if table.columns.iter().any(|c| c.created_at || c.updated_at) {
+        quote! { let now = easy_sqlx_core::sql::utils::value::Value::now(); }
+    } else {
+        quote! {}
+    }
+}
+
+/// 生成设置一行数据的语句，receiver 为数据来源变量（如 self），set 将 Pair 表达式包装为设置语句
+/// 创建时间及修改时间列使用 create_now 生成的当前时间，自增列不插入，由数据库生成
+/// use_default 为 true 时设置了 default 的 Option 字段值为 None 时不插入，使用数据库默认值
+fn create_insert_sets<F>(
+    table: &TableSchema,
+    receiver: &proc_macro2::TokenStream,
+    use_default: bool,
+    set: F,
+) -> Vec<proc_macro2::TokenStream>
+where
+    F: Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+{
+    let mut sets = vec![];
+    for (col, value) in table
+        .columns
+        .iter()
+        .zip(create_insert_values(table, receiver))
+    {
+        if col.autoincr {
+            continue;
+        }
+        let col_name = &col.get_column_name();
+        if col.created_at || col.updated_at {
+            sets.push(set(quote! {
+                easy_sqlx_core::sql::utils::pair::Pair {
                     name: #col_name.to_string(),
-                    value: easy_sqlx_core::sql::utils::value::Value::from(&self.#field_name.clone()),
-                });
+                    value: now.clone(),
+                }
+            }));
+        } else if use_default && col.nullable && col.default.is_some() {
+            let field_name =
+                proc_macro2::Ident::new(col.name.as_str(), proc_macro2::Span::call_site());
+            let pair = set(quote! {
+                easy_sqlx_core::sql::utils::pair::Pair {
+                    name: #col_name.to_string(),
+                    value: easy_sqlx_core::sql::utils::value::Value::from(v),
+                }
+            });
+            sets.push(quote! {
+                if let Some(v) = #receiver.#field_name.clone() {
+                    #pair
+                }
             });
+        } else {
+            sets.push(set(quote! {
+                easy_sqlx_core::sql::utils::pair::Pair {
+                    name: #col_name.to_string(),
+                    value: #value,
+                }
+            }));
         }
     }
+    sets
+}
+
+/// 主键列的查询名称，用于 returning
+fn pk_query_columns(table: &TableSchema) -> Vec<String> {
+    table
+        .columns
+        .iter()
+        .filter(|c| c.pk)
+        .map(|c| c.get_query_column_name())
+        .collect()
+}
+
+pub fn create_insert(table: &TableSchema) -> proc_macro2::TokenStream {
+    // insert 绑定参数
+    let this = proc_macro2::Ident::new("this", proc_macro2::Span::call_site());
+    // let mut insert_bind_args: Vec<proc_macro2::TokenStream> = Vec::new();
+    let insert_bind_args = create_insert_sets(table, &quote! { self }, true, |pair| {
+        quote! { builder = builder.set(easy_sqlx_core::sql::typed::Typed::unchecked(#pair)); }
+    });
+    let pk_cols = pk_query_columns(table);
+    let now = create_now(table);
 
-    let comment = format!("插入 {} 所有数据到表 {} 中", table.name, table.name_with_schema());
+    let comment = format!(
+        "插入 {} 数据到表 {} 中，自增列及值为 None 的默认值列由数据库生成，使用 execute_returning 执行时返回主键",
+        table.name,
+        table.name_with_schema()
+    );
     quote! {
         #[doc = #comment]
-        pub fn insert<'a>(&self) -> easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a> {
+        pub fn insert<'a>(&self) -> easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a, Self> {
             // let table = &Self::table();
             let #this = self;
-            let mut builder: easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a> = easy_sqlx_core::sql::builder::insert_builder::InsertBuilder::new(Self::table());
+            let mut builder: easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a, Self> = easy_sqlx_core::sql::builder::insert_builder::InsertBuilder::for_entity();
             // for col in Self::table().columns {
 
             // println!("insert 1");
+            #now
             #(#insert_bind_args) *
 
             // }
+            builder.returning(vec![#(#pk_cols), *] as Vec<&str>)
+        }
+    }
+}
+
+pub fn create_insert_many(table: &TableSchema) -> proc_macro2::TokenStream {
+    let row_sets = create_insert_sets(table, &quote! { item }, true, |pair| {
+        quote! { row.push(easy_sqlx_core::sql::typed::Typed::unchecked(#pair)); }
+    });
+    let pk_cols = pk_query_columns(table);
+    let now = create_now(table);
+
+    let comment = format!(
+        "使用一条 insert 语句插入多条 {} 数据到表 {} 中，参数过多时自动拆分为多条语句",
+        table.name,
+        table.name_with_schema()
+    );
+    quote! {
+        #[doc = #comment]
+        pub fn insert_many<'a>(items: &[Self]) -> easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a, Self> {
+            #now
+            let rows: Vec<Vec<easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::utils::pair::Pair>>> = items
+                .iter()
+                .map(|item| {
+                    let mut row = vec![];
+                    #(#row_sets) *
+                    row
+                })
+                .collect();
+            easy_sqlx_core::sql::builder::insert_builder::InsertBuilder::for_entity()
+                .rows(rows)
+                .returning(vec![#(#pk_cols), *] as Vec<&str>)
+        }
+    }
+}
+
+/// 生成 upsert 函数，没有主键的表不生成
+pub fn create_upsert(table: &TableSchema) -> proc_macro2::TokenStream {
+    let pk_cols: Vec<String> = table
+        .columns
+        .iter()
+        .filter(|c| c.pk)
+        .map(|c| c.get_column_name())
+        .collect();
+    if pk_cols.is_empty() {
+        return quote! {};
+    }
+    let insert_bind_args = create_insert_sets(table, &quote! { self }, false, |pair| {
+        quote! { builder = builder.set(easy_sqlx_core::sql::typed::Typed::unchecked(#pair)); }
+    });
+    let returning_cols = pk_query_columns(table);
+    let now = create_now(table);
+    // 冲突时保留原有的创建时间、自增列、乐观锁版本及软删除时间
+    let update_cols: Vec<String> = table
+        .columns
+        .iter()
+        .filter(|c| !c.pk && !c.created_at && !c.autoincr && !c.version)
+        .filter(|c| !table.is_soft_delete(c))
+        .map(|c| c.get_column_name())
+        .collect();
+
+    let comment = format!(
+        "插入 {} 所有数据到表 {} 中，主键冲突时更新其它列\n\n自增列由数据库生成，冲突时不更新创建时间、版本及软删除时间，不检查乐观锁",
+        table.name,
+        table.name_with_schema()
+    );
+    quote! {
+        #[doc = #comment]
+        pub fn upsert<'a>(&self) -> easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a, Self> {
+            let mut builder: easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a, Self> = easy_sqlx_core::sql::builder::insert_builder::InsertBuilder::for_entity();
+            #now
+            #(#insert_bind_args) *
             builder
+                .on_conflict(vec![#(#pk_cols), *])
+                .do_update_excluded(vec![#(#update_cols), *] as Vec<&str>)
+                .returning(vec![#(#returning_cols), *] as Vec<&str>)
         }
     }
 }
 
+pub fn create_copy_in(table: &TableSchema) -> proc_macro2::TokenStream {
+    // 与 insert 一致: 自增列由数据库生成，创建时间及修改时间列使用当前时间
+    // 与 insert 不同: copy 的列对所有行相同，设置了 default 的列为 None 时导入 null
+    let mut cols = vec![];
+    let mut row_values = vec![];
+    for (col, value) in table
+        .columns
+        .iter()
+        .zip(create_insert_values(table, &quote! { item }))
+    {
+        if col.autoincr {
+            continue;
+        }
+        cols.push(col.get_column_name());
+        if col.created_at || col.updated_at {
+            row_values.push(quote! { now.clone() });
+        } else {
+            row_values.push(value);
+        }
+    }
+    let now = create_now(table);
+
+    let comment = format!(
+        "使用 copy ... from stdin 批量导入 {} 数据到表 {} 中，自增列由数据库生成，发送时逐行编码\n\n与 insert 不同，copy 的列对所有行相同，设置了 default 的 Option 字段为 None 时导入 null",
+        table.name,
+        table.name_with_schema()
+    );
+    quote! {
+        #[doc = #comment]
+        pub fn copy_in<'a>(items: &'a [Self]) -> easy_sqlx_core::sql::builder::copy_builder::CopyInBuilder<'a> {
+            #now
+            let rows = items.iter().map(move |item| vec![#(#row_values), *]);
+            easy_sqlx_core::sql::builder::copy_builder::CopyInBuilder::new(Self::table_schema())
+                .columns(vec![#(#cols), *] as Vec<&str>)
+                .rows_iter(rows)
+        }
+    }
+}
 
 pub fn create_insert_builder() -> proc_macro2::TokenStream {
     quote! {
-        pub fn build_insert<'a>() -> easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a> {
-            let mut builder: easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a> = easy_sqlx_core::sql::builder::insert_builder::InsertBuilder::new(Self::table());
+        pub fn build_insert<'a>() -> easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a, Self> {
+            let mut builder: easy_sqlx_core::sql::builder::insert_builder::InsertBuilder<'a, Self> = easy_sqlx_core::sql::builder::insert_builder::InsertBuilder::for_entity();
             builder
         }
     }
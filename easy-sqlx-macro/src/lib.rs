@@ -1,8 +1,13 @@
 use condition::create_conditions;
+use easy_sqlx_core::sql::schema::types::rust_types::{R_CHRONO_DATETIME, R_CHRONO_DATETIME_FULL};
 use delete::{create_delete, create_delete_builder, create_delete_by_id};
-use field::create_field_wrapper;
-use heck::ToSnakeCase;
-use insert::{create_insert, create_insert_builder};
+use entity::create_entity;
+use field::{create_field_incr, create_field_wrapper};
+use from_row::create_from_row;
+use heck::{ToShoutySnakeCase, ToSnakeCase};
+use insert::{
+    create_copy_in, create_insert, create_insert_builder, create_insert_many, create_upsert,
+};
 use order::create_order_func;
 use proc_macro2::Span;
 use quote::quote;
@@ -12,7 +17,9 @@ use syn::{parse_macro_input, DeriveInput};
 mod attrs;
 mod condition;
 mod delete;
+mod entity;
 mod field;
+mod from_row;
 mod insert;
 mod update;
 mod select;
@@ -61,6 +68,8 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut col_names: Vec<String> = Vec::new();
     let mut cols = Vec::new();
 
+    // 带类型的列常量
+    let mut col_consts: Vec<proc_macro2::TokenStream> = Vec::new();
     // 列属性函数
     let mut col_wrapper_methods: Vec<proc_macro2::TokenStream> = Vec::new();
     // 条件属性函数
@@ -69,6 +78,8 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut col_order_methods: Vec<proc_macro2::TokenStream> = Vec::new();
 
     let mut struct_fields: Vec<syn::Field> = vec![];
+    // 忽略的字段
+    let mut ignored_fields: Vec<syn::Ident> = vec![];
 
     if let syn::Data::Struct(syn::DataStruct {
         struct_token: _,
@@ -87,6 +98,21 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                                 if column.pk && column.nullable {
                                     panic!("pk field must not nullable, consider remove type Option<> or remove pk props of field: {field_name}");
                                 } 
+                                if column.version
+                                    && (column.nullable
+                                        || !["i16", "i32", "i64"].contains(&rust_type.as_str()))
+                                {
+                                    panic!("version field must be a not null integer (i16, i32, i64): {field_name}");
+                                }
+                                if (column.created_at || column.updated_at)
+                                    && ![R_CHRONO_DATETIME, R_CHRONO_DATETIME_FULL]
+                                        .contains(&rust_type.as_str())
+                                {
+                                    panic!("created_at / updated_at field must be NaiveDateTime: {field_name}");
+                                }
+                                if column.created_at && column.updated_at {
+                                    panic!("created_at and updated_at can not be used on the same field: {field_name}");
+                                }
                                 
                                 // 生成列方法名称
                                 let fn_name = syn::Ident::new(
@@ -103,10 +129,26 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                                     }
                                 });
 
+                                // 生成带类型的列常量
+                                let const_name = syn::Ident::new(
+                                    field_name.to_shouty_snake_case().as_str(),
+                                    Span::call_site(),
+                                );
+                                let const_doc = format!("{col_name} 列");
+                                let field_ty = &field.ty;
+                                col_consts.push(quote! {
+                                    #[doc = #const_doc]
+                                    pub const #const_name: easy_sqlx_core::sql::typed::Column<Self, #syn_type, #field_ty> =
+                                        easy_sqlx_core::sql::typed::Column::new(#col_name);
+                                });
+
                                 // 生成列函数
                                 let wrappers =
                                     create_field_wrapper(&column, &field, syn_type, is_vec);
                                 col_wrapper_methods.extend(wrappers);
+                                col_wrapper_methods.extend(create_field_incr(
+                                    &column, syn_type, &rust_type, is_vec,
+                                ));
 
                                 // 生成条件属性函数
                                 let conds =
@@ -129,6 +171,9 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                                 cols.push(column);
                             }
                         }
+                    } else if let Some(ident) = field.ident.clone() {
+                        // #[col(ignore)] 的字段
+                        ignored_fields.push(ident);
                     }
                 }
                 Err(err) => {
@@ -154,9 +199,16 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         panic!("{}", err);
     }
 
+    if let Err(err) = table.check_soft_delete() {
+        panic!("{}", err);
+    }
+
     let table_name = table.name_with_schema();
 
     let insert = create_insert(&table);
+    let insert_many = create_insert_many(&table);
+    let copy_in = create_copy_in(&table);
+    let upsert = create_upsert(&table);
     let build_insert = create_insert_builder();
 
     let update = create_update(&table, &ident);
@@ -169,6 +221,9 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let build_select = create_select_builder();
     let select_by_id = create_select_by_id(&table, &ident, &struct_fields);
 
+    let entity = create_entity(&table, &ident, &struct_fields);
+    let from_row = create_from_row(&table, &ident, &ignored_fields);
+
     // 实现 comment 方法
     let output = quote! {
         impl #ident {
@@ -177,11 +232,20 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 #table_name
             }
 
+            /// 获取表结构定义，首次调用时生成，之后返回缓存的定义
+            pub fn table_schema() -> &'static easy_sqlx_core::sql::schema::table::TableSchema {
+                static TABLE: std::sync::OnceLock<easy_sqlx_core::sql::schema::table::TableSchema> =
+                    std::sync::OnceLock::new();
+                TABLE.get_or_init(|| #table)
+            }
+
             /// 获取表结构定义
             pub fn table() -> easy_sqlx_core::sql::schema::table::TableSchema {
-                #table
+                Self::table_schema().clone()
             }
 
+            #(#col_consts) *
+
             /// 列名称函数
             #(#col_name_methods) *
             /// 获取所有列名称
@@ -190,6 +254,9 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             }
 
             #insert
+            #insert_many
+            #copy_in
+            #upsert
             #build_insert
 
             #update
@@ -211,6 +278,10 @@ pub fn derive_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             //     [#(#cols), *].to_vec()
             // }
         }
+
+        #entity
+
+        #from_row
     };
     output.into()
 }
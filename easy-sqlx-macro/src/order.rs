@@ -6,20 +6,21 @@ pub fn create_order_func(col: &Column) -> proc_macro2::TokenStream {
     let asc_name = syn::Ident::new(format!("{}_asc", &col.name).as_str(), Span::call_site());
     let desc_name = syn::Ident::new(format!("{}_desc", &col.name).as_str(), Span::call_site());
     let order_name = syn::Ident::new(format!("{}_order", &col.name).as_str(), Span::call_site());
-    let field_name = col.name.clone();
+    // 排序使用数据库列名称，与条件一致
+    let col_name = col.get_column_name();
 
     quote! {
         // 升序
-        pub fn #asc_name() -> easy_sqlx_core::sql::dialects::page::Order {
-            easy_sqlx_core::sql::dialects::page::Order::asc(#field_name.to_string())
+        pub fn #asc_name() -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::dialects::page::Order> {
+            easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::dialects::page::Order::asc(#col_name.to_string()))
         }
         /// 降序
-        pub fn #desc_name() -> easy_sqlx_core::sql::dialects::page::Order {
-            easy_sqlx_core::sql::dialects::page::Order::desc(#field_name.to_string())
+        pub fn #desc_name() -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::dialects::page::Order> {
+            easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::dialects::page::Order::desc(#col_name.to_string()))
         }
         /// 默认排序
-        pub fn #order_name() -> easy_sqlx_core::sql::dialects::page::Order {
-            easy_sqlx_core::sql::dialects::page::Order::new(#field_name.to_string())
+        pub fn #order_name() -> easy_sqlx_core::sql::typed::Typed<Self, easy_sqlx_core::sql::dialects::page::Order> {
+            easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::dialects::page::Order::new(#col_name.to_string()))
         }
     }
 }
\ No newline at end of file
@@ -45,8 +45,8 @@ pub fn create_select_by_id(
     // let note = args.to_string();
     quote! {
         /// 根据 主键 查询
-        pub fn select_by_id<'a>(#(#id_args), *) -> easy_sqlx_core::sql::builder::select_builder::SelectBuilder<'a> {
-            let mut builder: easy_sqlx_core::sql::builder::select_builder::SelectBuilder<'a> = easy_sqlx_core::sql::builder::select_builder::SelectBuilder::new(Self::table());
+        pub fn select_by_id<'a>(#(#id_args), *) -> easy_sqlx_core::sql::builder::select_builder::SelectBuilder<'a, Self> {
+            let mut builder: easy_sqlx_core::sql::builder::select_builder::SelectBuilder<'a, Self> = easy_sqlx_core::sql::builder::select_builder::SelectBuilder::for_entity();
             #(#where_args) *
             // }
             builder
@@ -56,8 +56,8 @@ pub fn create_select_by_id(
 
 pub fn create_select_builder() -> proc_macro2::TokenStream {
     quote! {
-        pub fn select<'a>() -> easy_sqlx_core::sql::builder::select_builder::SelectBuilder<'a> {
-            easy_sqlx_core::sql::builder::select_builder::SelectBuilder::new(Self::table())
+        pub fn select<'a>() -> easy_sqlx_core::sql::builder::select_builder::SelectBuilder<'a, Self> {
+            easy_sqlx_core::sql::builder::select_builder::SelectBuilder::for_entity()
         }
     }
 }
@@ -7,7 +7,23 @@ pub fn create_update(table: &TableSchema, entity: &Ident) -> proc_macro2::TokenS
     let this = proc_macro2::Ident::new("this", proc_macro2::Span::call_site());
     // let mut insert_bind_args: Vec<proc_macro2::TokenStream> = Vec::new();
     let mut update_args = vec![];
+    let mut changed_args = vec![];
     let mut where_args = vec![];
+    // 乐观锁版本条件及版本加 1
+    let mut version_args = vec![];
+    let mut changed_version_args = vec![];
+    // 有版本列时记录是否有字段变化，没有变化时不更新版本
+    let has_version = table.columns.iter().any(|c| c.version);
+    let mark_changed = if has_version {
+        quote! { changed = true; }
+    } else {
+        quote! {}
+    };
+    let init_changed = if has_version {
+        quote! { let mut changed = false; }
+    } else {
+        quote! {}
+    };
     for col in table.columns.iter() {
         let field_name = proc_macro2::Ident::new(col.name.as_str(), proc_macro2::Span::call_site()); // &col.name;
         let col_name = &col.get_column_name();
@@ -35,45 +51,98 @@ pub fn create_update(table: &TableSchema, entity: &Ident) -> proc_macro2::TokenS
             continue;
         }
 
-        if col.nullable {
-            update_args.push(quote! {
+        if col.version {
+            // 版本列作为 where 条件，并且版本加 1
+            let col_eq = proc_macro2::Ident::new(
+                format!("{}_eq", &col.name).as_str(),
+                proc_macro2::Span::call_site(),
+            );
+            version_args.push(quote! {
+                builder = builder
+                    .and(#entity::#col_eq(self.#field_name.clone()))
+                    .set_expr(easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::SetExpr::incr(#col_name, 1)))
+                    .check_version();
+            });
+            changed_version_args.push(quote! {
+                if changed {
+                    builder = builder
+                        .and(#entity::#col_eq(original.#field_name.clone()))
+                        .set_expr(easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::SetExpr::incr(#col_name, 1)))
+                        .check_version();
+                }
+            });
+            continue;
+        }
+
+        if col.created_at || col.updated_at {
+            // 创建时间不修改，修改时间由 UpdateBuilder 自动设置
+            continue;
+        }
+
+        if table.is_soft_delete(col) {
+            // 删除时间只由 delete 设置，避免覆盖并发的删除
+            continue;
+        }
+
+        let set = if col.nullable {
+            quote! {
                 // let #this = self;
-                builder = builder.set(easy_sqlx_core::sql::utils::pair::Pair {
+                builder = builder.set(easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::Pair {
                     name: #col_name.to_string(),
                     value: easy_sqlx_core::sql::utils::value::Value::from(self.#field_name.clone()),
-                });
-            });
+                }));
+            }
         } else {
-            update_args.push(quote! {
+            quote! {
                 // let #this = self;
-                builder = builder.set(easy_sqlx_core::sql::utils::pair::Pair {
+                builder = builder.set(easy_sqlx_core::sql::typed::Typed::unchecked(easy_sqlx_core::sql::utils::pair::Pair {
                     name: #col_name.to_string(),
                     value: easy_sqlx_core::sql::utils::value::Value::from(&self.#field_name.clone()),
-                });
-            });
-        }
+                }));
+            }
+        };
+        // 只更新与原始数据不同的字段
+        changed_args.push(quote! {
+            if self.#field_name != original.#field_name {
+                #set
+                #mark_changed
+            }
+        });
+        update_args.push(set);
     }
     quote! {
-        /// 根据主键更新全部数据
-        pub fn update<'a>(&self) -> easy_sqlx_core::sql::builder::update_builder::UpdateBuilder<'a> {
+        /// 根据主键更新全部数据，有版本列时以版本作为条件并将版本加 1，不修改创建时间及软删除时间
+        pub fn update<'a>(&self) -> easy_sqlx_core::sql::builder::update_builder::UpdateBuilder<'a, Self> {
             // let table = &Self::table();
             let #this = self;
-            let mut builder: easy_sqlx_core::sql::builder::update_builder::UpdateBuilder<'a> = easy_sqlx_core::sql::builder::update_builder::UpdateBuilder::new(Self::table());
+            let mut builder: easy_sqlx_core::sql::builder::update_builder::UpdateBuilder<'a, Self> = easy_sqlx_core::sql::builder::update_builder::UpdateBuilder::for_entity();
             // for col in Self::table().columns {
 
             // println!("insert 1");
             #(#update_args) *
             #(#where_args) *
+            #(#version_args) *
             // }
             builder
         }
+
+        /// 根据主键更新与 original 不同的字段，没有字段变化时执行不会访问数据库
+        /// original 一般为从数据库中查询出的原始数据，避免覆盖其它字段的并发修改
+        pub fn update_changed<'a>(&self, original: &Self) -> easy_sqlx_core::sql::builder::update_builder::UpdateBuilder<'a, Self> {
+            let mut builder: easy_sqlx_core::sql::builder::update_builder::UpdateBuilder<'a, Self> = easy_sqlx_core::sql::builder::update_builder::UpdateBuilder::for_entity().skip_empty();
+            #init_changed
+            #(#changed_args) *
+            #(#where_args) *
+            #(#changed_version_args) *
+            builder
+        }
     }
 }
 
 pub fn create_update_builder() -> proc_macro2::TokenStream {
     quote! {
-        pub fn build_update<'a>() -> easy_sqlx_core::sql::builder::update_builder::UpdateBuilder<'a> {
-            easy_sqlx_core::sql::builder::update_builder::UpdateBuilder::new(Self::table())
+        pub fn build_update<'a>() -> easy_sqlx_core::sql::builder::update_builder::UpdateBuilder<'a, Self> {
+            easy_sqlx_core::sql::builder::update_builder::UpdateBuilder::for_entity()
         }
     }
 }
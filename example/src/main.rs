@@ -76,10 +76,7 @@ async fn main() {
     };
 
     let mut builder = User::build_update();
-    builder = builder.set(easy_sqlx_core::sql::utils::pair::Pair {
-        name: "123".to_string(),
-        value: easy_sqlx_core::sql::utils::value::Value::from(user.name.clone()),
-    });
+    builder = builder.set(User::NAME.set(user.name.clone()));
 
     // User::build_delete().execute(&mut conn).await.unwrap();
 
@@ -1,3 +1,5 @@
 pub use easy_sqlx_core::sql::dialects::condition::{Where, WhereAppend};
-pub use easy_sqlx_core::sync::{sync_tables, sync_tables_with_schema};
+pub use easy_sqlx_core::sql::entity::{Entities, Entity};
+pub use easy_sqlx_core::sql::repository::Repository;
+pub use easy_sqlx_core::sync::{sync, sync_tables, sync_tables_with_schema, sync_with_schema};
 pub use easy_sqlx_macro::Table;
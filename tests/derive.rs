@@ -0,0 +1,301 @@
+//! #[derive(Table)] 生成的语句，只生成 sql，不连接数据库
+
+// 生成的表结构中 Column 使用 ..Default::default()
+#![allow(clippy::needless_update)]
+
+use easy_sqlx::{Table, WhereAppend as _};
+use easy_sqlx_core::sql::utils::value::Value;
+
+#[derive(Table, Default, Debug, Clone)]
+#[table(name = "account")]
+pub struct Account {
+    #[col(pk, autoincr)]
+    pub id: i64,
+    pub name: String,
+    #[col(default = "'none'")]
+    pub memo: Option<String>,
+}
+
+fn account(name: &str) -> Account {
+    Account {
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn copy_in_writes_null_for_none_default_columns() {
+    let accounts = vec![account("a\tb")];
+    let (sql, data) = Account::copy_in(&accounts).to_sql().unwrap();
+    assert_eq!(sql, r#"copy "public"."account" ("name","memo") from stdin"#);
+    // insert 不插入 memo 使用默认值，copy 的列对所有行相同，导入 null
+    assert_eq!(data, "a\\tb\t\\N\n");
+}
+
+#[test]
+fn insert_skips_autoincrement_and_none_default_columns() {
+    let (sql, params) = account("a").insert().to_sql().unwrap();
+    assert_eq!(sql, r#"insert into "public"."account" ("name") values ($1)"#);
+    assert_eq!(params, vec![Value::from("a".to_string())]);
+
+    let item = Account {
+        id: 7,
+        memo: Some("m".to_string()),
+        ..account("a")
+    };
+    let (sql, params) = item.insert().to_sql().unwrap();
+    assert_eq!(
+        sql,
+        r#"insert into "public"."account" ("name","memo") values ($1,$2)"#
+    );
+    assert_eq!(params.len(), 2);
+}
+
+#[test]
+fn insert_many_uses_default_for_none_default_columns() {
+    let accounts = vec![
+        account("a"),
+        Account {
+            memo: Some("m".to_string()),
+            ..account("b")
+        },
+    ];
+    let (sql, params) = Account::insert_many(&accounts).to_sql().unwrap();
+    assert_eq!(
+        sql,
+        r#"insert into "public"."account" ("name","memo") values ($1,default),($2,$3)"#
+    );
+    assert_eq!(params.len(), 3);
+}
+
+#[test]
+fn update_changed_sets_only_changed_fields() {
+    let original = Account {
+        id: 1,
+        ..account("a")
+    };
+    let changed = Account {
+        memo: Some("m".to_string()),
+        ..original.clone()
+    };
+    let (sql, params) = changed.update_changed(&original).to_sql();
+    assert_eq!(
+        sql,
+        r#"update "public"."account" set "memo"=$1  where "id" = $2"#
+    );
+    assert_eq!(params, vec![Value::from("m".to_string()), Value::from(1i64)]);
+
+    // update 设置主键以外的全部字段
+    let (sql, _) = changed.update().to_sql();
+    assert_eq!(
+        sql,
+        r#"update "public"."account" set "name"=$1,"memo"=$2  where "id" = $3"#
+    );
+}
+
+#[derive(Table, Default, Debug, Clone)]
+#[table(name = "document")]
+pub struct Document {
+    #[col(pk)]
+    pub id: i64,
+    pub title: String,
+    #[col(version)]
+    pub version: i32,
+}
+
+#[test]
+fn update_checks_and_increments_version() {
+    let doc = Document {
+        id: 1,
+        title: "a".to_string(),
+        version: 3,
+    };
+    let (sql, params) = doc.update().to_sql();
+    assert_eq!(
+        sql,
+        r#"update "public"."document" set "title"=$1,"version"=coalesce("version", 0) + $2  where "id" = $3 and "version" = $4"#
+    );
+    assert_eq!(
+        params,
+        vec![
+            Value::from("a".to_string()),
+            Value::from(1),
+            Value::from(1i64),
+            Value::from(3),
+        ]
+    );
+
+    // 字段没有变化时不增加版本
+    let changed = Document {
+        title: "b".to_string(),
+        ..doc.clone()
+    };
+    let (sql, _) = changed.update_changed(&doc).to_sql();
+    assert!(sql.contains(r#""version"=coalesce("version", 0) + $2"#));
+    assert!(sql.ends_with(r#"where "id" = $3 and "version" = $4"#));
+    let (sql, _) = doc.update_changed(&doc).to_sql();
+    assert!(!sql.contains("version"));
+}
+
+#[derive(Table, Default, Debug, Clone)]
+#[table(name = "post")]
+pub struct Post {
+    #[col(pk)]
+    pub id: i64,
+    pub title: String,
+    #[col(created_at)]
+    pub created_at: chrono::NaiveDateTime,
+    #[col(updated_at)]
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[test]
+fn timestamps_are_stamped_with_current_time() {
+    let post = Post {
+        id: 1,
+        title: "a".to_string(),
+        ..Default::default()
+    };
+    let (sql, params) = post.insert().to_sql().unwrap();
+    assert_eq!(
+        sql,
+        r#"insert into "public"."post" ("id","title","created_at","updated_at") values ($1,$2,$3,$4)"#
+    );
+    // 插入时创建时间与修改时间相同，不使用结构体中的值
+    assert_eq!(params[2], params[3]);
+    assert_ne!(params[2], Value::from(post.created_at));
+
+    // 修改时不更新创建时间，修改时间为当前时间
+    let (sql, params) = post.update().to_sql();
+    assert_eq!(
+        sql,
+        r#"update "public"."post" set "title"=$1,"updated_at"=$2  where "id" = $3"#
+    );
+    assert_ne!(params[1], Value::from(post.updated_at));
+}
+
+#[derive(Table, Default, Debug, Clone)]
+#[table(name = "note", soft_delete = "deleted_at")]
+pub struct Note {
+    #[col(pk)]
+    pub id: i64,
+    pub body: String,
+    #[col(version)]
+    pub version: i32,
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+}
+
+#[test]
+fn upsert_keeps_generated_version_and_deleted_columns() {
+    let (sql, params) = account("a").upsert().to_sql().unwrap();
+    assert_eq!(
+        sql,
+        r#"insert into "public"."account" ("name","memo") values ($1,$2) on conflict ("id") do update set "name"=excluded."name","memo"=excluded."memo""#
+    );
+    assert_eq!(params.len(), 2);
+
+    let (sql, _) = Note::default().upsert().to_sql().unwrap();
+    assert_eq!(
+        sql,
+        r#"insert into "public"."note" ("id","body","version","deleted_at") values ($1,$2,$3,$4) on conflict ("id") do update set "body"=excluded."body""#
+    );
+}
+
+#[test]
+fn update_does_not_set_soft_delete_column() {
+    let (sql, _) = Note::default().update().to_sql();
+    assert_eq!(
+        sql,
+        r#"update "public"."note" set "body"=$1,"version"=coalesce("version", 0) + $2  where "id" = $3 and "version" = $4"#
+    );
+}
+
+#[test]
+fn is_new_checks_autoincrement_primary_key() {
+    use easy_sqlx::Entity;
+
+    assert!(Entity::is_new(&account("a")));
+    assert!(!Entity::is_new(&Account {
+        id: 1,
+        ..account("a")
+    }));
+    // 没有自增主键时无法判断
+    assert!(!Entity::is_new(&Note::default()));
+}
+
+#[test]
+fn typed_columns_compare_within_entity() {
+    use easy_sqlx_core::sql::dialects::condition::Operator;
+
+    let (sql, params) = Post::select()
+        .and(Post::UPDATED_AT.compare(Operator::Gt, Post::CREATED_AT))
+        .and(Post::ID.is_in(vec![1, 2]))
+        .to_sql();
+    assert_eq!(
+        sql,
+        r#"select "id","title","created_at","updated_at" from "public"."post"  where "updated_at" > "created_at" and "id" in ($1,$2)  "#
+    );
+    assert_eq!(params, vec![Value::from(1i64), Value::from(2i64)]);
+}
+
+#[derive(Table, Default, Debug, Clone)]
+#[table(name = "member")]
+pub struct Member {
+    #[col(pk)]
+    pub id: i64,
+    #[col(column = "abc")]
+    pub name: String,
+}
+
+#[test]
+fn order_uses_database_column_name() {
+    let (sql, _) = Member::select()
+        .order_by(Member::name_asc())
+        .order_by(Member::NAME.desc().nulls_last())
+        .to_sql();
+    assert_eq!(
+        sql,
+        r#"select "id","abc" as "name" from "public"."member"   order by "abc" asc, "abc" desc nulls last "#
+    );
+}
+
+#[derive(Table, Default, Debug, Clone)]
+#[table(name = "profile", from_row)]
+pub struct Profile {
+    #[col(pk)]
+    pub id: i64,
+    #[col(column = "nick_name")]
+    pub name: String,
+    #[col(ignore)]
+    pub score: i32,
+}
+
+/// 只执行查询语句，不需要建表: DATABASE_URL=postgres://... cargo test -- --ignored
+#[tokio::test]
+#[ignore = "requires DATABASE_URL"]
+async fn from_row_reads_by_column_name() {
+    use easy_sqlx_core::sqlx::{self, Connection as _, PgConnection};
+
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL");
+    let mut conn = PgConnection::connect(&url).await.unwrap();
+
+    // 按数据库列名称读取
+    let profile: Profile = sqlx::query_as(r#"select 7::int8 as "id", 'a' as "nick_name""#)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!((profile.id, profile.name.as_str(), profile.score), (7, "a", 0));
+
+    // 生成的查询将重命名的列别名为字段名称
+    let profile: Profile = sqlx::query_as(r#"select 8::int8 as "id", 'b' as "name""#)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!((profile.id, profile.name.as_str()), (8, "b"));
+
+    let err = sqlx::query_as::<_, Profile>(r#"select 9::int8 as "id""#)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, sqlx::Error::ColumnNotFound(_)));
+}